@@ -2,6 +2,10 @@
 
 layout(binding = 1) uniform sampler2D texSampler;
 
+// Größe des Atlas in Pixeln, wird beim Erstellen der Pipeline gesetzt
+layout(constant_id = 0) const float ATLAS_WIDTH = 1024.0;
+layout(constant_id = 1) const float ATLAS_HEIGHT = 1024.0;
+
 layout(location = 0) in vec2 fragTexCoord;
 layout(location = 1) in flat uint fragUvStart;
 layout(location = 2) in flat uint fragUvSize;
@@ -11,6 +15,6 @@ layout(location = 0) out vec4 outColor;
 void main() {
     vec2 uv_start = vec2(fragUvStart & 0xFFFF, (fragUvStart >> 16) & 0xFFFF);
     vec2 uv_size = vec2(fragUvSize & 0xFFFF, (fragUvSize >> 16) & 0xFFFF);
-    // Ausgabe der Farbe mit Alpha = 1.0
-    outColor = texture(texSampler, (fragTexCoord * uv_size + uv_start) / 1024);
+    // REPEAT des Samplers würde über den ganzen Atlas wiederholen, fract bleibt im Rechteck des Sprites
+    outColor = texture(texSampler, (fract(fragTexCoord) * uv_size + uv_start) / vec2(ATLAS_WIDTH, ATLAS_HEIGHT));
 }
\ No newline at end of file
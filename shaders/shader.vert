@@ -7,7 +7,7 @@ layout(binding = 0) uniform UniformBufferObject {
 
 layout(location = 0) in vec2 inPosition;
 layout(location = 1) in vec2 inSize;
-layout(location = 2) in uint inId;
+layout(location = 2) in uint inRepeat;
 layout(location = 3) in uint inUvStart;
 layout(location = 4) in uint inUvSize;
 
@@ -18,7 +18,8 @@ layout(location = 2) out uint fragUvSize;
 void main() {
     vec2 uv = vec2(((gl_VertexIndex << 1) & 2) >> 1, (gl_VertexIndex & 2) >> 1);
     gl_Position = ubo.proj * ubo.view * vec4(uv * inSize + inPosition, 0.0, 1.0);
-    fragTexCoord = uv;
+    // Gekachelte Sprites wiederholen ihr Bild, inRepeat enthält Spalten und Zeilen wie inUvSize
+    fragTexCoord = uv * vec2(inRepeat & 0xFFFF, inRepeat >> 16);
     fragUvStart = inUvStart;
     fragUvSize = inUvSize;
 }
\ No newline at end of file
@@ -0,0 +1,189 @@
+// Packt die einzelnen Sprites aus textures/sprites in texture.png und schreibt das passende Manifest texture.atlas.
+//
+// vudeljump-pack [--input <dir>] [--texture <path>] [--manifest <path>] [--padding <px>]
+
+use std::{cmp::Reverse, fs::File, io::BufWriter, path::{Path, PathBuf}, process::exit};
+
+use atlas::{Atlas, Sprite};
+
+#[allow(dead_code)]
+#[path = "../graphic/atlas.rs"]
+mod atlas;
+
+struct Options {
+    input: PathBuf,
+    texture: PathBuf,
+    manifest: PathBuf,
+    padding: u32,
+}
+
+struct Image {
+    name: String,
+    width: u32,
+    height: u32,
+    // RGBA, 8 Bit pro Kanal
+    pixels: Vec<u8>,
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|err| {
+        eprintln!("{err}");
+        eprintln!("usage: vudeljump-pack [--input <dir>] [--texture <path>] [--manifest <path>] [--padding <px>]");
+        exit(2);
+    });
+
+    if let Err(err) = run(&options) {
+        eprintln!("{err}");
+        exit(1);
+    }
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let images = load_images(&options.input)?;
+    if images.is_empty() {
+        return Err(format!("no png files in {}", options.input.display()));
+    }
+
+    let sizes: Vec<(u32, u32)> = images.iter().map(|image| (image.width, image.height)).collect();
+    let (width, height, positions) = pack(&sizes, options.padding);
+
+    let mut pixels = vec![0; (width * height * 4) as usize];
+    let mut manifest = String::from("# Erzeugt von vudeljump-pack aus textures/sprites, nicht von Hand bearbeiten\n# name x y breite höhe\n");
+    for (image, &(x, y)) in images.iter().zip(&positions) {
+        for row in 0..image.height {
+            let src = (row * image.width * 4) as usize;
+            let dst = (((y + row) * width + x) * 4) as usize;
+            let len = (image.width * 4) as usize;
+            pixels[dst..dst + len].copy_from_slice(&image.pixels[src..src + len]);
+        }
+        manifest.push_str(&format!("{} {x} {y} {} {}\n", image.name, image.width, image.height));
+    }
+
+    // Das Manifest muss sich mit demselben Code lesen lassen, den das Spiel benutzt
+    let atlas = Atlas::parse(&manifest)?;
+    for (image, &(x, y)) in images.iter().zip(&positions) {
+        let sprite = Sprite { x: x as u16, y: y as u16, width: image.width as u16, height: image.height as u16 };
+        if atlas.get(&image.name) != Some(sprite) {
+            return Err(format!("sprite {} does not survive the manifest", image.name));
+        }
+    }
+
+    write_png(&options.texture, width, height, &pixels)?;
+    std::fs::write(&options.manifest, manifest).map_err(|err| format!("could not write {}: {err}", options.manifest.display()))?;
+
+    println!("packed {} sprites into {width} x {height}", images.len());
+    Ok(())
+}
+
+fn parse_args() -> Result<Options, String> {
+    let textures = Path::new(env!("CARGO_MANIFEST_DIR")).join("textures");
+    let mut options = Options { input: textures.join("sprites"), texture: textures.join("texture.png"), manifest: textures.join("texture.atlas"), padding: 1 };
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {arg}"));
+
+        match arg.as_str() {
+            "--input" => options.input = value()?.into(),
+            "--texture" => options.texture = value()?.into(),
+            "--manifest" => options.manifest = value()?.into(),
+            "--padding" => options.padding = value()?.parse().map_err(|err| format!("invalid padding: {err}"))?,
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+
+    Ok(options)
+}
+
+// Alle PNGs des Ordners, nach Namen sortiert, damit gleiche Eingaben denselben Atlas ergeben
+fn load_images(dir: &Path) -> Result<Vec<Image>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|err| format!("could not read {}: {err}", dir.display()))?;
+    let mut paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).filter(|path| path.extension().is_some_and(|ext| ext == "png")).collect();
+    paths.sort();
+
+    paths.iter().map(|path| load_image(path)).collect()
+}
+
+fn load_image(path: &Path) -> Result<Image, String> {
+    let name = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default().to_owned();
+    if name.is_empty() || name.contains(char::is_whitespace) || name.starts_with('#') {
+        return Err(format!("{}: file name can not be used as sprite name", path.display()));
+    }
+
+    let file = File::open(path).map_err(|err| format!("could not open {}: {err}", path.display()))?;
+    let mut decoder = png::Decoder::new(file);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16 | png::Transformations::ALPHA);
+    let mut reader = decoder.read_info().map_err(|err| format!("{}: {err}", path.display()))?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).map_err(|err| format!("{}: {err}", path.display()))?;
+    buf.truncate(info.buffer_size());
+
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::GrayscaleAlpha => buf.chunks_exact(2).flat_map(|pixel| [pixel[0], pixel[0], pixel[0], pixel[1]]).collect(),
+        color => return Err(format!("{}: unsupported color type {color:?}", path.display())),
+    };
+
+    if info.width > u16::MAX as u32 || info.height > u16::MAX as u32 {
+        return Err(format!("{}: sprite is too large", path.display()));
+    }
+
+    Ok(Image { name, width: info.width, height: info.height, pixels })
+}
+
+fn write_png(path: &Path, width: u32, height: u32, pixels: &[u8]) -> Result<(), String> {
+    let file = File::create(path).map_err(|err| format!("could not create {}: {err}", path.display()))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|err| format!("{}: {err}", path.display()))?;
+    writer.write_image_data(pixels).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+// Regal-Packer: Sprites nach Höhe sortiert zeilenweise einsortieren. Die Breite ist eine Zweierpotenz,
+// passt die Höhe nicht in dieselbe Größe, wird die Breite verdoppelt und neu gepackt.
+fn pack(sizes: &[(u32, u32)], padding: u32) -> (u32, u32, Vec<(u32, u32)>) {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&index| (Reverse(sizes[index].1), Reverse(sizes[index].0)));
+
+    let area: u32 = sizes.iter().map(|&(width, height)| (width + padding) * (height + padding)).sum();
+    let widest = sizes.iter().map(|&(width, _)| width).max().unwrap_or(1);
+    let mut width = ((area as f32).sqrt().ceil() as u32).max(widest).next_power_of_two();
+
+    loop {
+        let mut positions = vec![(0, 0); sizes.len()];
+        let (mut x, mut y, mut shelf) = (0, 0, 0);
+        for &index in &order {
+            let (sprite_width, sprite_height) = sizes[index];
+            if x + sprite_width > width {
+                x = 0;
+                y += shelf;
+                shelf = 0;
+            }
+            positions[index] = (x, y);
+            x += sprite_width + padding;
+            shelf = shelf.max(sprite_height + padding);
+        }
+
+        // Der Abstand unter dem letzten Regal wird nicht gebraucht
+        let height = (y + shelf).saturating_sub(padding).max(1).next_power_of_two();
+        if height <= width {
+            return (width, height, positions);
+        }
+        width *= 2;
+    }
+}
+
+#[test]
+fn packed_sprites_stay_inside_and_do_not_overlap() {
+    let sizes = [(128, 256), (30, 6), (30, 6), (16, 16), (12, 12), (9, 7), (200, 3)];
+    let (width, height, positions) = pack(&sizes, 1);
+    assert!(width.is_power_of_two() && height.is_power_of_two());
+
+    for (a, (&(ax, ay), &(aw, ah))) in positions.iter().zip(&sizes).enumerate() {
+        assert!(ax + aw <= width && ay + ah <= height);
+        for (&(bx, by), &(bw, bh)) in positions.iter().zip(&sizes).skip(a + 1) {
+            assert!(ax + aw <= bx || bx + bw <= ax || ay + ah <= by || by + bh <= ay);
+        }
+    }
+}
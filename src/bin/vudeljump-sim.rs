@@ -0,0 +1,164 @@
+// Führt die Spielwelt ohne Fenster und ohne Vulkan aus und gibt Statistiken pro Lauf aus.
+//
+// vudeljump-sim [--seed <u64>] [--ticks <u64>] [--runs <u32>] [--policy idle|ai|replay:<path>] [--difficulty <name>] [--config <path>] [--wrap]
+
+use std::{path::Path, process::exit};
+
+use game::{difficulty::{self, Difficulty}, replay::{Input, Replay}, states::build_main, world::TICK_RATE, World};
+
+#[allow(dead_code)]
+#[path = "../graphic/mod.rs"]
+mod graphic;
+#[allow(dead_code)]
+#[path = "../game/mod.rs"]
+mod game;
+
+// Wie weit die KI über sich nach Plattformen sucht, während der Spieler steigt
+const AI_REACH: f32 = 150.0;
+const AI_DEAD_ZONE: f32 = 5.0;
+
+enum Policy {
+    Idle,
+    Ai,
+    Replay(String),
+}
+
+struct Options {
+    seed: u64,
+    ticks: u64,
+    runs: u32,
+    policy: Policy,
+    difficulty: String,
+    presets: Vec<Difficulty>,
+    wrap: bool,
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|err| {
+        eprintln!("{err}");
+        eprintln!("usage: vudeljump-sim [--seed <u64>] [--ticks <u64>] [--runs <u32>] [--policy idle|ai|replay:<path>] [--difficulty <name>] [--config <path>] [--wrap]");
+        exit(2);
+    });
+
+    println!("{:>4} {:>20} {:>8} {:>8} {:>10} {:>10} {:>6} {:>6}", "run", "seed", "ticks", "score", "death", "platforms", "items", "kills");
+
+    let mut best_score = 0;
+    let mut total_score = 0u64;
+
+    for run in 0..options.runs {
+        let seed = options.seed.wrapping_add(run as u64);
+        let mut world = World::create_with_seed(build_main(), seed, options.presets.clone());
+        world.difficulty = options.presets.iter().position(|preset| preset.name == options.difficulty).unwrap() as u8;
+        world.wrap = options.wrap;
+        world.restart_with_seed(seed);
+
+        if let Policy::Replay(path) = &options.policy {
+            match Replay::load(Path::new(path)) {
+                Ok(replay) => world.start_replay(replay),
+                Err(err) => {
+                    eprintln!("could not load replay {path}: {err}");
+                    exit(1);
+                }
+            }
+        }
+
+        let start_tick = world.tick;
+        while world.tick - start_tick < options.ticks && !world.dead {
+            if let Policy::Ai = options.policy {
+                steer(&mut world);
+            }
+            world.update(1.0 / TICK_RATE as f32);
+        }
+
+        let stats = world.stats;
+        let death = stats.death_height.map_or("-".to_string(), |height| format!("{height:.0}"));
+        println!("{:>4} {:>20} {:>8} {:>8} {:>10} {:>10} {:>6} {:>6}", run, world.seed, world.tick - start_tick, world.score, death, stats.platforms_generated, stats.items_collected, stats.enemies_killed);
+
+        best_score = best_score.max(world.score);
+        total_score += world.score as u64;
+    }
+
+    println!("max score: {best_score}, mean score: {:.1}", total_score as f64 / options.runs as f64);
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options { seed: rand::random(), ticks: 60 * TICK_RATE as u64, runs: 1, policy: Policy::Ai, difficulty: "normal".to_string(), presets: difficulty::default_presets(), wrap: false };
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {arg}"));
+
+        match arg.as_str() {
+            "--seed" => options.seed = value()?.parse().map_err(|err| format!("invalid seed: {err}"))?,
+            "--ticks" => options.ticks = value()?.parse().map_err(|err| format!("invalid tick count: {err}"))?,
+            "--runs" => options.runs = value()?.parse().map_err(|err| format!("invalid run count: {err}"))?,
+            "--policy" => {
+                let policy = value()?;
+                options.policy = match policy.as_str() {
+                    "idle" => Policy::Idle,
+                    "ai" => Policy::Ai,
+                    _ => match policy.strip_prefix("replay:") {
+                        Some(path) => Policy::Replay(path.to_string()),
+                        None => return Err(format!("unknown policy {policy}")),
+                    },
+                };
+            },
+            "--difficulty" => options.difficulty = value()?,
+            "--config" => {
+                let path = value()?;
+                let text = std::fs::read_to_string(&path).map_err(|err| format!("could not read {path}: {err}"))?;
+                options.presets = difficulty::parse_presets(&text).map_err(|err| format!("invalid config {path}: {err}"))?;
+            },
+            "--wrap" => options.wrap = true,
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+
+    if !options.presets.iter().any(|preset| preset.name == options.difficulty) {
+        return Err(format!("unknown difficulty {}", options.difficulty));
+    }
+
+    if options.runs == 0 {
+        return Err("--runs must be at least 1".to_string());
+    }
+
+    Ok(options)
+}
+
+// Steuert auf die Plattform zu, auf der der Spieler als nächstes landen kann
+fn steer(world: &mut World) {
+    let player = &world.player;
+    let bottom = player.pos.y - player.size.y;
+    let center = player.pos.x + player.size.x * 0.5;
+
+    let target = if player.velocity.y > 0.0 {
+        world.platforms.iter().filter(|platform| platform.pos.y > bottom && platform.pos.y < bottom + AI_REACH).min_by(|a, b| a.pos.y.total_cmp(&b.pos.y))
+    } else {
+        world.platforms.iter().filter(|platform| platform.pos.y <= bottom).max_by(|a, b| a.pos.y.total_cmp(&b.pos.y))
+    };
+
+    let movement = match target {
+        Some(platform) => {
+            let diff = platform.pos.x + platform.size.x * 0.5 - center;
+            if diff > AI_DEAD_ZONE { 1 } else if diff < -AI_DEAD_ZONE { -1 } else { 0 }
+        },
+        None => 0,
+    };
+
+    // Die KI drückt nur Tasten, die Bewegung ist also immer -1, 0 oder 1
+    let current = player.movement as i8;
+    if movement == current {
+        return;
+    }
+
+    match current {
+        -1 => world.input(Input::MoveLeft(false)),
+        1 => world.input(Input::MoveRight(false)),
+        _ => (),
+    }
+    match movement {
+        -1 => world.input(Input::MoveLeft(true)),
+        1 => world.input(Input::MoveRight(true)),
+        _ => (),
+    }
+}
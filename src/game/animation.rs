@@ -0,0 +1,147 @@
+use crate::graphic::atlas::{self, Sprite};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoopMode {
+    // Bleibt auf dem letzten Bild stehen
+    Once,
+    Loop,
+    // Läuft vorwärts und wieder zurück, ohne die Randbilder doppelt zu zeigen
+    PingPong,
+}
+
+// Ein Bild eines Clips, `sprite` ist der Name im Atlas-Manifest
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frame {
+    pub sprite: &'static str,
+    pub duration: f32,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Clip {
+    pub frames: &'static [Frame],
+    pub mode: LoopMode,
+}
+
+const fn frame(sprite: &'static str, duration: f32) -> Frame {
+    Frame { sprite, duration }
+}
+
+impl Clip {
+    pub const PLAYER_IDLE: Clip = Clip { frames: &[frame("player", 0.4), frame("player_idle", 0.4)], mode: LoopMode::PingPong };
+    pub const PLAYER_JUMP: Clip = Clip { frames: &[frame("player_squash", 0.06), frame("player_stretch", 0.18), frame("player", 0.1)], mode: LoopMode::Once };
+    pub const PLAYER_FALL: Clip = Clip { frames: &[frame("player", 0.15), frame("player_fall", 0.15)], mode: LoopMode::Loop };
+    pub const PLATFORM_BREAK: Clip = Clip { frames: &[frame("platform_breaking_0", 0.05), frame("platform_breaking_1", 0.08), frame("platform_broken", 0.1)], mode: LoopMode::Once };
+    pub const ITEM_PICKUP: Clip = Clip { frames: &[frame("item_pickup_0", 0.05), frame("item_pickup_1", 0.07), frame("item_pickup_2", 0.1)], mode: LoopMode::Once };
+
+    pub fn length(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.duration).sum()
+    }
+
+    // Index des Bildes, das `time` Sekunden nach dem Start zu sehen ist
+    pub fn frame_at(&self, time: f32) -> usize {
+        let count = self.frames.len();
+        let length = self.length();
+        if count < 2 || length <= 0.0 {
+            return 0;
+        }
+
+        let mut time = match self.mode {
+            LoopMode::Once if time >= length => return count - 1,
+            LoopMode::Once => time,
+            LoopMode::Loop => time.rem_euclid(length),
+            LoopMode::PingPong => {
+                // Der Rückweg lässt das letzte und das erste Bild aus
+                let back = length - self.frames[0].duration - self.frames[count - 1].duration;
+                let time = time.rem_euclid(length + back);
+                if time >= length {
+                    let mut time = time - length;
+                    for index in (1..count - 1).rev() {
+                        if time < self.frames[index].duration {
+                            return index;
+                        }
+                        time -= self.frames[index].duration;
+                    }
+                    return 0;
+                }
+                time
+            },
+        };
+
+        for (index, frame) in self.frames.iter().enumerate() {
+            if time < frame.duration {
+                return index;
+            }
+            time -= frame.duration;
+        }
+        count - 1
+    }
+}
+
+// Abspielzustand eines Clips, läuft mit der Simulationszeit
+#[derive(Debug, Clone, Copy)]
+pub struct Animation {
+    pub clip: &'static Clip,
+    pub time: f32,
+}
+
+impl Animation {
+    pub const fn new(clip: &'static Clip) -> Self {
+        Self { clip, time: 0.0 }
+    }
+
+    // Startet einen anderen Clip von vorn, der laufende Clip läuft ungestört weiter
+    pub fn play(&mut self, clip: &'static Clip) {
+        if self.clip != clip {
+            *self = Self::new(clip);
+        }
+    }
+
+    #[inline]
+    pub fn update(&mut self, delta_time: f32) {
+        self.time += delta_time;
+    }
+
+    #[inline]
+    pub fn finished(&self) -> bool {
+        self.clip.mode == LoopMode::Once && self.time >= self.clip.length()
+    }
+
+    #[inline]
+    pub fn frame(&self) -> Frame {
+        self.clip.frames[self.clip.frame_at(self.time)]
+    }
+
+    #[inline]
+    pub fn sprite(&self) -> Sprite {
+        atlas::sprite(self.frame().sprite)
+    }
+}
+
+#[test]
+fn clips_follow_their_loop_mode() {
+    const FRAMES: [Frame; 3] = [frame("a", 1.0), frame("b", 1.0), frame("c", 1.0)];
+    const ONCE: Clip = Clip { frames: &FRAMES, mode: LoopMode::Once };
+    const LOOP: Clip = Clip { frames: &FRAMES, mode: LoopMode::Loop };
+    const PING_PONG: Clip = Clip { frames: &FRAMES, mode: LoopMode::PingPong };
+
+    let times = [0.5, 1.5, 2.5, 3.5, 4.5, 5.5];
+    assert_eq!(times.map(|time| ONCE.frame_at(time)), [0, 1, 2, 2, 2, 2]);
+    assert_eq!(times.map(|time| LOOP.frame_at(time)), [0, 1, 2, 0, 1, 2]);
+    assert_eq!(times.map(|time| PING_PONG.frame_at(time)), [0, 1, 2, 1, 0, 1]);
+
+    let mut animation = Animation::new(&ONCE);
+    animation.update(1.5);
+    animation.play(&ONCE);
+    assert_eq!(animation.frame().sprite, "b");
+    animation.update(1.5);
+    assert!(animation.finished());
+    animation.play(&LOOP);
+    assert_eq!((animation.frame().sprite, animation.finished()), ("a", false));
+
+    // Alle Bilder der Spiel-Clips stehen im Atlas
+    for clip in [&Clip::PLAYER_IDLE, &Clip::PLAYER_JUMP, &Clip::PLAYER_FALL, &Clip::PLATFORM_BREAK, &Clip::ITEM_PICKUP] {
+        for frame in clip.frames {
+            assert_ne!(atlas::sprite(frame.sprite), Sprite::default(), "{} is missing", frame.sprite);
+        }
+    }
+}
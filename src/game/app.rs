@@ -1,19 +1,22 @@
 
 use std::{cell::RefCell, mem::{MaybeUninit, ManuallyDrop}, ops::Not, rc::Rc, thread::sleep, time::{Duration, Instant}};
 
-use cgmath::Vector2;
-use iron_oxide::{primitives::Vec2, ui::{UiEvent, UiState}};
+use iron_oxide::{primitives::Vec2, ui::UiEvent};
 use log::info;
-use winit::{application::ApplicationHandler, dpi::{PhysicalPosition, PhysicalSize}, event::{self, ElementState, MouseButton, WindowEvent}, event_loop::ActiveEventLoop, keyboard::KeyCode, window::WindowId};
+use winit::{application::ApplicationHandler, dpi::{PhysicalPosition, PhysicalSize}, event::{self, ElementState, MouseButton, WindowEvent}, event_loop::ActiveEventLoop, keyboard::{KeyCode, PhysicalKey}, window::WindowId};
 
-use crate::graphic::VulkanRender;
+use crate::graphic::{capture::Capture, VulkanRender};
 
-use super::{states::build_main, World};
+use super::{controls::{quantize_steer, Action, Binding, Controls, TiltSensor, CONTROLS_FILE}, difficulty::{self, DIFFICULTY_FILE}, gamepad::{Gamepads, PadEvent}, highscore::{HighScores, HIGHSCORE_FILE}, playfield::{self, Viewport}, replay::{Input, Replay}, states::{build_highscores, build_main, GameState, MenuAction, StateStack}, storage, world::TICK_TIME, World};
 
-const WIDTH: u32 = 400;
-const HEIGHT: u32 = 600;
 const FPS: f32 = 144.0;
 const FRAME_TIME: f32 = 1.0 / FPS;
+const REPLAY_FILE: &str = "replay.vjr";
+// Fingerweg relativ zur Breite der Spielfläche, ab dem mit voller Geschwindigkeit gelenkt wird
+const TOUCH_RANGE: f32 = 0.2;
+const TOUCH_DEADZONE: f32 = 0.05;
+// Etwa eine Sekunde bei 60 Bildern pro Sekunde
+const BURST_FRAMES: u32 = 60;
 
 #[allow(dead_code)]
 pub struct App {
@@ -22,11 +25,20 @@ pub struct App {
     pub cursor_pos: PhysicalPosition<f64>,
     pub world: World,
     pub time: Instant,
-    pub ui: Rc<RefCell<UiState>>,
-    pub last_cursor_location: Vec2,
+    pub states: StateStack,
+    // Startpunkt des Fingers, gelenkt wird über den Abstand dazu
+    pub touch_origin: Vec2,
     pub touch_id: u64,
     pub mouse_pressed: bool,
-    pub sim_speed: f32,
+    pub highscores: HighScores,
+    pub controls: Controls,
+    pub gamepads: Gamepads,
+    // Aktion, die auf ihre neue Taste wartet
+    pub rebinding: Option<Action>,
+    // Neigungssensor, wird auf Android von außen gesetzt
+    pub tilt: Option<Box<dyn TiltSensor>>,
+    // Zuletzt gesendete analoge Lenkung
+    pub steer: f32,
 }
 
 impl App {
@@ -35,19 +47,284 @@ impl App {
     pub fn run() -> Self {
         #[allow(invalid_value)]
         let renderer= Rc::new(RefCell::new(ManuallyDrop::new(unsafe { MaybeUninit::uninit().assume_init() })));
-        let ui: Rc<RefCell<UiState>> = Rc::new(RefCell::new(build_main()));
-        let world = World::create(ui.clone());
+        let hud = build_main();
+        let world = World::create(hud.clone(), difficulty::load_presets(&storage::data_file(DIFFICULTY_FILE)));
+        let highscores = HighScores::load(&storage::data_file(HIGHSCORE_FILE));
+        let states = StateStack::create(hud, build_highscores(&highscores));
 
-        Self {
+        let mut app = Self {
             renderer,
             init: false,
             cursor_pos: PhysicalPosition { x: 0.0, y: 0.0 },
             world, time: Instant::now(),
-            ui,
-            last_cursor_location: Vec2::zero(),
+            states,
+            touch_origin: Vec2::zero(),
             touch_id: 0,
             mouse_pressed: false,
-            sim_speed: 1.0,
+            highscores,
+            controls: Controls::load(&storage::data_file(CONTROLS_FILE)),
+            gamepads: Gamepads::create(),
+            rebinding: None,
+            tilt: None,
+            steer: 0.0,
+        };
+        app.update_difficulty_label();
+        app.update_wrap_label();
+        app.update_camera_label();
+        app.update_binding_labels();
+        app.update_tilt_label();
+        app
+    }
+
+    // Verbindet die Buttons mit dem Zustandsstapel. Die Buttons zeigen danach auf `self.states`, die App darf also
+    // bis zum Ende der Eventschleife nicht mehr verschoben werden.
+    #[allow(dead_code)]
+    pub unsafe fn connect_ui(&mut self) {
+        let (title, paused, game_over, settings, highscores) = (self.states.title.clone(), self.states.paused.clone(), self.states.game_over.clone(), self.states.settings.clone(), self.states.highscores.clone());
+        let states = &mut self.states;
+        unsafe {
+            title.play.on_press(states, StateStack::on_play);
+            title.settings.on_press(states, StateStack::on_settings);
+            title.highscores.on_press(states, StateStack::on_highscores);
+            paused.resume.on_press(states, StateStack::on_resume);
+            paused.settings.on_press(states, StateStack::on_settings);
+            paused.title.on_press(states, StateStack::on_title);
+            paused.screenshot.on_press(states, StateStack::on_screenshot);
+            game_over.restart.on_press(states, StateStack::on_play);
+            game_over.title.on_press(states, StateStack::on_title);
+            settings.difficulty.on_press(states, StateStack::on_next_difficulty);
+            settings.back.on_press(states, StateStack::on_back);
+            settings.wrap.on_press(states, StateStack::on_toggle_wrap);
+            settings.camera.on_press(states, StateStack::on_next_camera);
+            settings.tilt.on_press(states, StateStack::on_toggle_tilt);
+            for (action, handle) in &settings.bindings {
+                if let Some(callback) = StateStack::rebind_callback(*action) {
+                    handle.on_press(states, callback);
+                }
+            }
+            highscores.back.on_press(states, StateStack::on_back);
+        }
+    }
+
+    // Führt Menüaktionen aus und folgt dem Zustand der Welt (Tod, Neustart durch ein Replay)
+    fn update_states(&mut self) {
+        if let Some(action) = self.states.pending.take() {
+            self.apply_menu_action(action);
+        }
+
+        match self.states.top() {
+            GameState::Playing if self.world.dead => {
+                self.states.game_over.score.set_text(&format!("Score: {}", self.world.score));
+                self.transition(|states| states.replace(GameState::GameOver));
+            },
+            GameState::GameOver if !self.world.dead => self.transition(|states| states.replace(GameState::Playing)),
+            _ => (),
+        }
+    }
+
+    fn apply_menu_action(&mut self, action: MenuAction) {
+        // Jede andere Menüaktion bricht eine laufende Neubelegung ab
+        if self.rebinding.take().is_some() {
+            self.update_binding_labels();
+        }
+
+        match action {
+            MenuAction::Play => {
+                self.world.replay = None;
+                self.world.restart();
+                self.transition(|states| states.reset(GameState::Playing));
+            },
+            MenuAction::Pause => self.transition(|states| states.push(GameState::Paused)),
+            MenuAction::Resume | MenuAction::Back => self.transition(StateStack::pop),
+            MenuAction::Settings => self.transition(|states| states.push(GameState::Settings)),
+            MenuAction::Highscores => {
+                if self.states.top() == GameState::Highscores {
+                    return self.transition(StateStack::pop);
+                }
+                // Die Tabelle wird bei jedem Öffnen neu aufgebaut
+                self.states.highscores = build_highscores(&self.highscores);
+                let back = self.states.highscores.back.clone();
+                // Die App liegt seit connect_ui fest, wie alle anderen Buttons zeigt auch dieser auf self.states
+                unsafe { back.on_press(&mut self.states, StateStack::on_back) };
+                self.transition(|states| states.push(GameState::Highscores));
+            },
+            MenuAction::Title => self.transition(|states| states.reset(GameState::Title)),
+            MenuAction::NextDifficulty => {
+                self.world.next_difficulty();
+                self.update_difficulty_label();
+            },
+            MenuAction::ToggleWrap => {
+                self.world.toggle_wrap();
+                self.update_wrap_label();
+            },
+            // Die Kamera ist reine Darstellung und landet deshalb nicht im Replay
+            MenuAction::NextCamera => {
+                self.world.camera.smoothing = self.world.camera.smoothing.next();
+                self.update_camera_label();
+            },
+            MenuAction::ToggleTilt => {
+                self.controls.tilt.enabled = !self.controls.tilt.enabled;
+                if let Err(err) = self.controls.save(&storage::data_file(CONTROLS_FILE)) {
+                    log::error!("could not save controls: {err}");
+                }
+                self.update_tilt_label();
+            },
+            // Ohne Tastatur der einzige Weg zum Screenshot, das Pausenmenü selbst kommt nicht mit aufs Bild
+            MenuAction::Screenshot => self.renderer.borrow_mut().request_capture(Capture::screenshot(storage::data_dir(), true)),
+            MenuAction::Rebind(action) => {
+                self.rebinding = Some(action);
+                if let Some((_, handle)) = self.states.settings.bindings.iter().find(|(bound, _)| *bound == action) {
+                    handle.set_text(&format!("{}: press a key", action.label()));
+                }
+            },
+        }
+    }
+
+    fn on_action(&mut self, action: Action, pressed: bool) {
+        match action {
+            Action::MoveLeft => self.world.input(Input::MoveLeft(pressed)),
+            Action::MoveRight => self.world.input(Input::MoveRight(pressed)),
+            _ if !pressed => (),
+            Action::Pause => match self.states.top() {
+                GameState::Playing => self.apply_menu_action(MenuAction::Pause),
+                GameState::Paused | GameState::Settings | GameState::Highscores => self.apply_menu_action(MenuAction::Back),
+                _ => (),
+            },
+            Action::ToggleHud => {
+                let renderer = self.renderer.borrow();
+                let mut value = renderer.ui_state.borrow_mut();
+                value.visible = value.visible.not();
+                value.dirty = true;
+            },
+            // Neustart ohne Maus, aber nicht mitten im Lauf
+            Action::Restart => {
+                if matches!(self.states.top(), GameState::Title | GameState::Paused | GameState::GameOver) {
+                    self.apply_menu_action(MenuAction::Play);
+                }
+            },
+            Action::Highscores => self.apply_menu_action(MenuAction::Highscores),
+            Action::SaveReplay => match self.world.recorder.save(&storage::data_file(REPLAY_FILE)) {
+                Ok(()) => info!("saved replay to {}", storage::data_file(REPLAY_FILE).display()),
+                Err(err) => log::error!("could not save replay: {err}"),
+            },
+            Action::LoadReplay => match Replay::load(&storage::data_file(REPLAY_FILE)) {
+                Ok(replay) => {
+                    self.world.start_replay(replay);
+                    self.update_difficulty_label();
+                    self.update_wrap_label();
+                    self.transition(|states| states.reset(GameState::Playing));
+                },
+                Err(err) => log::error!("could not load replay: {err}"),
+            },
+            // Einzelner Tick, solange pausiert
+            Action::Step => {
+                if self.states.top() == GameState::Paused {
+                    self.world.update(TICK_TIME);
+                }
+            },
+            Action::ToggleRenderer => {
+                let mut renderer = self.renderer.borrow_mut();
+                renderer.renderer = !renderer.renderer;
+            },
+            Action::Screenshot => self.renderer.borrow_mut().request_capture(Capture::screenshot(storage::data_dir(), false)),
+            Action::Burst => self.renderer.borrow_mut().request_capture(Capture::burst(storage::data_dir(), BURST_FRAMES)),
+        }
+    }
+
+    // Escape bricht ab, jede andere Taste ersetzt die bisherigen Tasten der Aktion
+    fn finish_rebind(&mut self, action: Action, key: KeyCode) {
+        if key != KeyCode::Escape {
+            // Die Aufforderung bleibt offen, bis eine speicherbare Taste kommt
+            if !self.controls.rebind_key(action, key) {
+                log::warn!("{key:?} can not be bound, press another key");
+                return;
+            }
+            if let Err(err) = self.controls.save(&storage::data_file(CONTROLS_FILE)) {
+                log::error!("could not save controls: {err}");
+            }
+        }
+        self.rebinding = None;
+        self.update_binding_labels();
+    }
+
+    fn poll_gamepads(&mut self) {
+        for event in self.gamepads.poll() {
+            match event {
+                PadEvent::Button(button, pressed) => {
+                    if let Some(action) = self.controls.action(Binding::Pad(button)) {
+                        self.on_action(action, pressed);
+                    }
+                },
+                PadEvent::Steer(value) => self.steer(value),
+            }
+        }
+    }
+
+    // Neigungssteuerung als Quelle für die analoge Lenkung. Nur im Spiel, damit die Menüs ruhig bleiben.
+    fn poll_tilt(&mut self) {
+        let Some(tilt) = &self.tilt else {
+            return;
+        };
+        if self.controls.tilt.enabled && self.states.top() == GameState::Playing {
+            let value = self.controls.tilt.steer(tilt.acceleration_x());
+            self.steer(value);
+        }
+    }
+
+    // Sendet die analoge Lenkung nur bei Änderungen, damit das Replay klein bleibt
+    fn steer(&mut self, value: f32) {
+        if value != self.steer {
+            self.steer = value;
+            self.world.input(Input::Steer(value));
+        }
+    }
+
+    // Die Sensordaten kommen aus dem Android-Modul, die App liest sie pro Frame
+    #[allow(dead_code)]
+    pub fn set_tilt_source(&mut self, tilt: Box<dyn TiltSensor>) {
+        self.tilt = Some(tilt);
+        self.update_tilt_label();
+    }
+
+    // Ändert den Stapel und tauscht die angezeigte UI, falls sich der oberste Zustand geändert hat.
+    // Nur die UI des obersten Zustands hat Grafikressourcen.
+    fn transition(&mut self, change: impl FnOnce(&mut StateStack)) {
+        let previous = self.states.top();
+        change(&mut self.states);
+        let next = self.states.top();
+
+        if previous == next || !self.init {
+            return;
+        }
+
+        let mut renderer = self.renderer.borrow_mut();
+        unsafe { renderer.base.device.device_wait_idle().unwrap_unchecked() };
+        renderer.ui_state.borrow().destroy(&renderer.base.device);
+
+        renderer.attach_ui(self.states.ui(next).clone());
+    }
+
+    fn update_difficulty_label(&mut self) {
+        self.states.settings.difficulty.set_text(&format!("Difficulty: {}", self.world.current_difficulty().name));
+    }
+
+    fn update_wrap_label(&mut self) {
+        self.states.settings.wrap.set_text(if self.world.wrap { "Wrap around: on" } else { "Wrap around: off" });
+    }
+
+    fn update_camera_label(&mut self) {
+        self.states.settings.camera.set_text(&format!("Camera: {}", self.world.camera.smoothing.name()));
+    }
+
+    fn update_tilt_label(&mut self) {
+        let tilt = &self.states.settings.tilt;
+        tilt.set_visible(self.tilt.is_some());
+        tilt.set_text(if self.controls.tilt.enabled { "Tilt steering: on" } else { "Tilt steering: off" });
+    }
+
+    fn update_binding_labels(&mut self) {
+        for (action, handle) in &self.states.settings.bindings {
+            handle.set_text(&format!("{}: {}", action.label(), self.controls.describe(*action)));
         }
     }
 }
@@ -91,22 +368,25 @@ impl ApplicationHandler for App {
                         if touch.id != 0 || self.touch_id != touch.id {return;}
                         self.touch_id = touch.id;
                         renderer.ui_state.borrow_mut().update_cursor(Vec2::new(renderer.window_size.width as f32, renderer.window_size.height as f32), cursor_pos, UiEvent::Press);
-                        self.last_cursor_location = cursor_pos;
+                        self.touch_origin = cursor_pos;
                     },
                     event::TouchPhase::Moved => {
                         let in_ui = renderer.ui_state.borrow_mut().update_cursor(Vec2::new(renderer.window_size.width as f32, renderer.window_size.height as f32), cursor_pos, UiEvent::Move);
-                        
-                        if in_ui < 2 {
-                            let diff = cursor_pos - self.last_cursor_location;
-                            self.world.player.pos += Vector2::new(diff.x, 0.0);
+
+                        // Der Abstand zum Startpunkt lenkt wie ein Stick, statt den Spieler zu versetzen
+                        if in_ui < 2 && self.states.top() == GameState::Playing {
+                            let playfield = Viewport::fit(renderer.window_size.width, renderer.window_size.height);
+                            let value = (cursor_pos.x - self.touch_origin.x) / (playfield.width * TOUCH_RANGE);
+                            drop(renderer);
+                            self.steer(quantize_steer(value, TOUCH_DEADZONE));
                         }
-                        
-                        self.last_cursor_location = cursor_pos;
                     },
                     //ended
                     _ => {
                         self.touch_id = 0;
                         renderer.ui_state.borrow_mut().update_cursor(Vec2::new(renderer.window_size.width as f32, renderer.window_size.height as f32), cursor_pos, UiEvent::Release);
+                        drop(renderer);
+                        self.steer(0.0);
                     }
                 }
             },
@@ -115,7 +395,15 @@ impl ApplicationHandler for App {
                 let time_stamp = self.time.elapsed().as_secs_f32();
                 if time_stamp > FRAME_TIME * 0.92 {
                     self.time = Instant::now();
-                    self.world.update(self.sim_speed * time_stamp);
+                    // Außerhalb von Playing steht die Simulation still
+                    if self.states.top() == GameState::Playing {
+                        self.world.update(time_stamp);
+                    }
+                    if let Some(entry) = self.world.finished_run.take()
+                        && self.highscores.submit(entry).is_some()
+                        && let Err(err) = self.highscores.save(&storage::data_file(HIGHSCORE_FILE)) {
+                        log::error!("could not save highscores: {err}");
+                    }
                     let mut renderer = self.renderer.borrow_mut();
                     renderer.draw_frame();
                 } else {
@@ -124,51 +412,20 @@ impl ApplicationHandler for App {
 
             },
             event::WindowEvent::KeyboardInput { device_id: _, event, is_synthetic: _ } => {
-                match event.physical_key {
-                    winit::keyboard::PhysicalKey::Code(key_code) => {
-
-                        match key_code {
-                            KeyCode::F1 => {
-                                if event.state.is_pressed() {
-                                    {
-                                        let mut value = renderer.ui_state.borrow_mut();
-                                        value.visible = value.visible.not();
-                                        value.dirty = true;
-                                    }
-                                }
-                            },
-                            KeyCode::KeyX => {
-                                if event.state.is_pressed() {
-                                    if self.sim_speed == 0.0 {
-                                        self.sim_speed = 1.0;
-                                    } else {
-                                        self.sim_speed = 0.0;
-                                    }
-                                }
-                            },
-                            KeyCode::KeyM => {
-                                if event.state.is_pressed() {
-                                    renderer.renderer = !renderer.renderer;
-                                }
-                            },
-                            KeyCode::KeyA => {
-                                if event.state.is_pressed() {
-                                    self.world.player.movement = -1;
-                                } else if self.world.player.movement == -1 {
-                                    self.world.player.movement = 0;
-                                }
-                            },
-                            KeyCode::KeyD => {
-                                if event.state.is_pressed() {
-                                    self.world.player.movement = 1;
-                                } else if self.world.player.movement == 1 {
-                                    self.world.player.movement = 0;
-                                }
-                            }
-                            _ => ()
-                        }
-                    },
-                    _ => ()
+                drop(renderer);
+                let PhysicalKey::Code(key_code) = event.physical_key else {
+                    return;
+                };
+                if event.repeat {
+                    return;
+                }
+
+                if let Some(action) = self.rebinding {
+                    if event.state.is_pressed() {
+                        self.finish_rebind(action, key_code);
+                    }
+                } else if let Some(action) = self.controls.action(Binding::Key(key_code)) {
+                    self.on_action(action, event.state.is_pressed());
                 }
             },
             event::WindowEvent::Resized(new_size) => {
@@ -176,14 +433,13 @@ impl ApplicationHandler for App {
                 if !self.init {
                     return;
                 }
-                let size = renderer.window.inner_size();
+                let size = renderer.window().inner_size();
                 if new_size != size || new_size == renderer.window_size {
                     return;
                 }
                 renderer.recreate_swapchain(size);
                 renderer.update_ui(new_size);
-                renderer.window.request_redraw();
-                self.world.view_end = self.world.view_start + new_size.height;
+                renderer.window().request_redraw();
             },
             event::WindowEvent::CloseRequested => {
                 event_loop.exit();
@@ -197,11 +453,17 @@ impl ApplicationHandler for App {
         if !self.init {
             return;
         }
-        self.renderer.borrow().window.request_redraw();
+        self.poll_gamepads();
+        self.poll_tilt();
+        self.update_states();
+        self.renderer.borrow().window().request_redraw();
     }
 
     fn suspended(&mut self, event_loop: &ActiveEventLoop) {
         println!("suspended");
+        if let Some(tilt) = &mut self.tilt {
+            tilt.set_enabled(false);
+        }
         if !self.init {
             return;
         }
@@ -215,20 +477,19 @@ impl ApplicationHandler for App {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         self.init = true;
         println!("resumed");
-        let window_attributes = winit::window::Window::default_attributes().with_title("Vudeljump").with_inner_size(PhysicalSize {width: WIDTH, height: HEIGHT}).with_min_inner_size(PhysicalSize {width: WIDTH, height: HEIGHT});
+        if let Some(tilt) = &mut self.tilt {
+            tilt.set_enabled(true);
+        }
+        let window_attributes = winit::window::Window::default_attributes().with_title("Vudeljump").with_inner_size(PhysicalSize {width: playfield::WIDTH as u32, height: playfield::HEIGHT as u32}).with_min_inner_size(PhysicalSize {width: playfield::WIDTH as u32 / 2, height: playfield::HEIGHT as u32 / 2});
         let window = event_loop.create_window(window_attributes).unwrap();
         let mut renderer = self.renderer.borrow_mut();
         *renderer = ManuallyDrop::new(VulkanRender::create(window, &self.world));
 
-        self.ui.borrow_mut().init_graphics(&renderer.base, &renderer.window_size, renderer.render_pass, &renderer.ui_descriptor_set_layout);
-        let window_size = renderer.window_size;
-        renderer.update_ui(window_size);
+        renderer.attach_ui(self.states.ui(self.states.top()).clone());
 
         event_loop.set_control_flow(winit::event_loop::ControlFlow::Poll);
         self.time = Instant::now();
         renderer.draw_frame();
-
-        self.world.view_end = self.world.view_start + window_size.height;
     }
 
     fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
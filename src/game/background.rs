@@ -0,0 +1,82 @@
+use cgmath::Vector2;
+
+use crate::graphic::{atlas, Layer, SpriteBatch};
+
+use super::playfield::{HEIGHT, WIDTH};
+
+// Eine über die Spielfläche gekachelte Hintergrundebene
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backdrop {
+    pub sprite: &'static str,
+    // Größe einer Kachel in Spieleinheiten, unabhängig von der Größe des Sprites
+    pub tile: Vector2<f32>,
+    // Anteil der Kamerabewegung, den die Ebene mitmacht: 0 steht still, 1 läuft mit den Plattformen
+    pub scroll: f32,
+}
+
+// Hintergrund ab einer bestimmten Höhe, die Ebenen werden von hinten nach vorn gezeichnet
+#[derive(Debug, PartialEq)]
+pub struct Biome {
+    pub name: &'static str,
+    pub height: f32,
+    pub layers: &'static [Backdrop],
+}
+
+const fn backdrop(sprite: &'static str, tile_width: f32, tile_height: f32, scroll: f32) -> Backdrop {
+    Backdrop { sprite, tile: Vector2 { x: tile_width, y: tile_height }, scroll }
+}
+
+// Nach Höhe sortiert, die erste Zone beginnt am Boden
+pub const BIOMES: [Biome; 3] = [
+    Biome { name: "meadow", height: 0.0, layers: &[backdrop("bg_sky_meadow", WIDTH, HEIGHT, 0.0), backdrop("bg_clouds_meadow", 200.0, 200.0, 0.15), backdrop("bg_leaves_meadow", 160.0, 160.0, 0.4)] },
+    Biome { name: "dusk", height: 5000.0, layers: &[backdrop("bg_sky_dusk", WIDTH, HEIGHT, 0.0), backdrop("bg_clouds_dusk", 200.0, 200.0, 0.15), backdrop("bg_birds_dusk", 180.0, 180.0, 0.35)] },
+    Biome { name: "space", height: 15000.0, layers: &[backdrop("bg_sky_space", WIDTH, HEIGHT, 0.0), backdrop("bg_stars_space", 128.0, 128.0, 0.05), backdrop("bg_planets_space", 256.0, 256.0, 0.2)] },
+];
+
+pub fn biome_at(height: f32) -> &'static Biome {
+    BIOMES.iter().rev().find(|biome| height >= biome.height).unwrap_or(&BIOMES[0])
+}
+
+impl Backdrop {
+    // Eine Fläche, die den Bildschirm plus eine Kachel Rand für das Wackeln der Kamera bedeckt.
+    // `view` ist die Unterkante der Kamera, zurückgegeben wird die linke obere Ecke wie bei allen Sprites.
+    pub fn placement(&self, view: f32) -> (Vector2<f32>, (u16, u16)) {
+        let columns = (WIDTH / self.tile.x).ceil() as u16 + 2;
+        let rows = (HEIGHT / self.tile.y).ceil() as u16 + 3;
+
+        // Steigt die Kamera, rutscht das Muster um `scroll` mal die Strecke nach unten
+        let phase = (view * self.scroll).rem_euclid(self.tile.y);
+        let top = -HEIGHT - view + phase - 2.0 * self.tile.y;
+
+        (Vector2 { x: -self.tile.x, y: top }, (columns, rows))
+    }
+}
+
+pub fn draw(view: f32, batch: &mut SpriteBatch) {
+    for backdrop in biome_at(view).layers {
+        let (position, repeat) = backdrop.placement(view);
+        batch.draw_tiled(Layer::Background, position, backdrop.tile, repeat, atlas::sprite(backdrop.sprite));
+    }
+}
+
+#[test]
+fn backdrops_cover_the_view_in_every_biome() {
+    assert_eq!(biome_at(0.0).name, "meadow");
+    assert_eq!(biome_at(4999.0).name, "meadow");
+    assert_eq!(biome_at(5000.0).name, "dusk");
+    assert_eq!(biome_at(1.0e6).name, "space");
+
+    for biome in &BIOMES {
+        for backdrop in biome.layers {
+            assert_ne!(atlas::sprite(backdrop.sprite), atlas::Sprite::default(), "{} is missing", backdrop.sprite);
+
+            for view in [0.0, 123.4, 5000.0, 98765.0] {
+                let (position, (columns, rows)) = backdrop.placement(view);
+                // Sichtbar ist y von -HEIGHT - view bis -view, mit Rand für das Wackeln
+                let (top, bottom) = (-HEIGHT - view, -view);
+                assert!(position.x <= -backdrop.tile.x * 0.5 && position.x + backdrop.tile.x * columns as f32 >= WIDTH + backdrop.tile.x * 0.5);
+                assert!(position.y <= top - backdrop.tile.y * 0.5 && position.y + backdrop.tile.y * rows as f32 >= bottom + backdrop.tile.y * 0.5);
+            }
+        }
+    }
+}
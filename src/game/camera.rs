@@ -0,0 +1,168 @@
+use cgmath::{ortho, Matrix4, Point3, Vector2, Vector3};
+
+use super::playfield::{HEIGHT, WIDTH};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Smoothing {
+    // Springt sofort auf das Ziel
+    Instant,
+    // Holt pro Sekunde einen festen Anteil des Abstands auf
+    Exponential(f32),
+    // Feder ohne Überschwingen, der Wert ist die ungefähre Zeit bis zum Ziel
+    CriticallyDamped(f32),
+}
+
+impl Smoothing {
+    // Auswahl im Einstellungsmenü, Exponential(15) entspricht der früheren Kamera
+    pub const PRESETS: [Smoothing; 3] = [Self::CriticallyDamped(0.12), Self::Exponential(15.0), Self::Instant];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Instant => "instant",
+            Self::Exponential(_) => "classic",
+            Self::CriticallyDamped(_) => "smooth",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::PRESETS.iter().position(|preset| *preset == self).map_or(0, |index| index + 1);
+        Self::PRESETS[index % Self::PRESETS.len()]
+    }
+}
+
+// Besitzt Position, Zoom und Wackeln der Kamera. Die Welt gibt nur das Ziel vor, der Renderer bekommt fertige Matrizen.
+#[derive(Debug)]
+pub struct Camera {
+    // Unterkante des sichtbaren Bereichs in Welteinheiten
+    pub pos: f32,
+    prev_pos: f32,
+    velocity: f32,
+    pub zoom: f32,
+    pub smoothing: Smoothing,
+    // 0 bis 1, das Wackeln wächst quadratisch damit und klingt von selbst ab
+    pub trauma: f32,
+    shake: Vector2<f32>,
+    prev_shake: Vector2<f32>,
+    time: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Camera {
+    // Ab dieser Steiggeschwindigkeit schaut die Kamera voraus, etwa bei Feder oder Jetpack
+    const LOOK_AHEAD_SPEED: f32 = 600.0;
+    const LOOK_AHEAD_TIME: f32 = 0.25;
+    pub const LOOK_AHEAD_MAX: f32 = 150.0;
+    const TRAUMA_DECAY: f32 = 1.5;
+    const MAX_SHAKE: f32 = 12.0;
+    const SHAKE_FREQUENCY: f32 = 25.0;
+
+    pub const fn new() -> Self {
+        Self {
+            pos: 0.0,
+            prev_pos: 0.0,
+            velocity: 0.0,
+            zoom: 1.0,
+            smoothing: Smoothing::PRESETS[0],
+            trauma: 0.0,
+            shake: Vector2 { x: 0.0, y: 0.0 },
+            prev_shake: Vector2 { x: 0.0, y: 0.0 },
+            time: 0.0,
+        }
+    }
+
+    // `target` ist die gewünschte Unterkante, `speed` die Steiggeschwindigkeit des Spielers
+    pub fn update(&mut self, delta_time: f32, target: f32, speed: f32) {
+        self.prev_pos = self.pos;
+        self.prev_shake = self.shake;
+        self.time += delta_time;
+
+        let look_ahead = ((speed - Self::LOOK_AHEAD_SPEED) * Self::LOOK_AHEAD_TIME).clamp(0.0, Self::LOOK_AHEAD_MAX);
+        let target = target + look_ahead;
+
+        match self.smoothing {
+            Smoothing::Instant => self.pos = target,
+            Smoothing::Exponential(rate) => self.pos += (target - self.pos) * (rate * delta_time).min(1.0),
+            Smoothing::CriticallyDamped(smooth_time) => self.pos = smooth_damp(self.pos, target, &mut self.velocity, smooth_time, delta_time),
+        }
+
+        self.trauma = (self.trauma - Self::TRAUMA_DECAY * delta_time).max(0.0);
+        let strength = self.trauma * self.trauma * Self::MAX_SHAKE;
+        self.shake = Vector2 { x: noise(self.time * Self::SHAKE_FREQUENCY, 0.0), y: noise(self.time * Self::SHAKE_FREQUENCY, 17.0) } * strength;
+    }
+
+    #[inline]
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+
+    // Springt ohne Übergang an die Position, etwa beim Neustart
+    pub fn reset(&mut self, pos: f32) {
+        *self = Self { pos, prev_pos: pos, zoom: self.zoom, smoothing: self.smoothing, ..Self::new() };
+    }
+
+    // Zwischen zwei Ticks interpolierte Unterkante
+    #[inline]
+    pub fn view(&self, alpha: f32) -> f32 {
+        self.prev_pos + (self.pos - self.prev_pos) * alpha
+    }
+
+    // View- und Projektionsmatrix für den Renderer. Die Welt wird mit negiertem y gezeichnet.
+    pub fn matrices(&self, alpha: f32) -> (Matrix4<f32>, Matrix4<f32>) {
+        let shake = self.prev_shake + (self.shake - self.prev_shake) * alpha;
+        let eye_y = -HEIGHT - self.view(alpha) - shake.y;
+
+        let view = Matrix4::look_at_rh(Point3::new(shake.x, eye_y, 1.0), Point3::new(shake.x, eye_y, 0.0), Vector3::unit_y());
+
+        // Gezoomt wird um die Mitte der Spielfläche
+        let (half_width, half_height) = (WIDTH * 0.5 / self.zoom, HEIGHT * 0.5 / self.zoom);
+        let proj = ortho(WIDTH * 0.5 - half_width, WIDTH * 0.5 + half_width, HEIGHT * 0.5 - half_height, HEIGHT * 0.5 + half_height, -100.0, 100.0);
+
+        (view, proj)
+    }
+}
+
+// Kritisch gedämpfte Feder nach "Game Programming Gems 4", Kapitel 1.10
+fn smooth_damp(current: f32, target: f32, velocity: &mut f32, smooth_time: f32, delta_time: f32) -> f32 {
+    let omega = 2.0 / smooth_time.max(0.0001);
+    let x = omega * delta_time;
+    let decay = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+    let change = current - target;
+    let temp = (*velocity + omega * change) * delta_time;
+    *velocity = (*velocity - omega * temp) * decay;
+    target + (change + temp) * decay
+}
+
+// Glattes Rauschen zwischen -1 und 1 aus überlagerten Sinuswellen, damit das Wackeln nicht vom Zufallsgenerator der Welt abhängt
+#[inline]
+fn noise(t: f32, offset: f32) -> f32 {
+    ((t + offset).sin() * 0.5 + (t * 2.3 + offset * 1.7).sin() * 0.3 + (t * 4.1 + offset * 0.6).sin() * 0.2).clamp(-1.0, 1.0)
+}
+
+#[test]
+fn camera_settles_without_overshoot_and_shake_fades() {
+    let mut camera = Camera::new();
+    let mut max = 0.0f32;
+    for _ in 0..240 {
+        camera.update(1.0 / 120.0, 100.0, 0.0);
+        max = max.max(camera.pos);
+    }
+    assert!(max <= 100.0);
+    assert!((camera.pos - 100.0).abs() < 0.5);
+
+    camera.update(1.0 / 120.0, 100.0, 1000.0);
+    assert!(camera.pos > 100.0);
+
+    camera.add_trauma(1.0);
+    camera.update(1.0 / 120.0, 100.0, 0.0);
+    assert!(camera.shake.x != 0.0 || camera.shake.y != 0.0);
+    for _ in 0..120 {
+        camera.update(1.0 / 120.0, 100.0, 0.0);
+    }
+    assert_eq!(camera.trauma, 0.0);
+    assert_eq!(camera.shake, Vector2 { x: 0.0, y: 0.0 });
+}
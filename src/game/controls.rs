@@ -0,0 +1,380 @@
+use std::{fs, io::{self, ErrorKind}, path::Path};
+
+use winit::keyboard::KeyCode;
+
+pub const CONTROLS_FILE: &str = "controls.cfg";
+const GRAVITY: f32 = 9.81;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Pause,
+    ToggleHud,
+    Restart,
+    Highscores,
+    SaveReplay,
+    LoadReplay,
+    Step,
+    ToggleRenderer,
+    Screenshot,
+    // Nimmt eine Reihe aufeinanderfolgender Frames auf
+    Burst,
+}
+
+impl Action {
+    pub const ALL: [Action; 12] = [Self::MoveLeft, Self::MoveRight, Self::Pause, Self::ToggleHud, Self::Restart, Self::Highscores, Self::SaveReplay, Self::LoadReplay, Self::Step, Self::ToggleRenderer, Self::Screenshot, Self::Burst];
+
+    // Name in der Konfigurationsdatei
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::MoveLeft => "move_left",
+            Self::MoveRight => "move_right",
+            Self::Pause => "pause",
+            Self::ToggleHud => "toggle_hud",
+            Self::Restart => "restart",
+            Self::Highscores => "highscores",
+            Self::SaveReplay => "save_replay",
+            Self::LoadReplay => "load_replay",
+            Self::Step => "step",
+            Self::ToggleRenderer => "toggle_renderer",
+            Self::Screenshot => "screenshot",
+            Self::Burst => "burst",
+        }
+    }
+
+    // Anzeigename im Einstellungsmenü
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::MoveLeft => "Move left",
+            Self::MoveRight => "Move right",
+            Self::Pause => "Pause",
+            Self::ToggleHud => "Toggle HUD",
+            Self::Restart => "Restart",
+            Self::Highscores => "Highscores",
+            Self::SaveReplay => "Save replay",
+            Self::LoadReplay => "Load replay",
+            Self::Step => "Single tick",
+            Self::ToggleRenderer => "Toggle renderer",
+            Self::Screenshot => "Screenshot",
+            Self::Burst => "Record burst",
+        }
+    }
+}
+
+// Unabhängig von gilrs, damit die Konfiguration auch ohne Gamepad-Unterstützung gelesen werden kann
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+impl PadButton {
+    const ALL: [PadButton; 12] = [Self::South, Self::East, Self::North, Self::West, Self::LeftTrigger, Self::RightTrigger, Self::Select, Self::Start, Self::DPadUp, Self::DPadDown, Self::DPadLeft, Self::DPadRight];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+    Key(KeyCode),
+    Pad(PadButton),
+}
+
+impl Binding {
+    fn parse(name: &str) -> Option<Self> {
+        match name.strip_prefix("Pad:") {
+            Some(button) => PadButton::ALL.into_iter().find(|candidate| format!("{candidate:?}") == button).map(Self::Pad),
+            None => KEYS.iter().find(|candidate| format!("{candidate:?}") == name).map(|&key| Self::Key(key)),
+        }
+    }
+
+    fn encode(self) -> String {
+        match self {
+            Self::Key(key) => format!("{key:?}"),
+            Self::Pad(button) => format!("Pad:{button:?}"),
+        }
+    }
+
+    // Kurzform für das Menü, "KeyA" wird zu "A"
+    pub fn describe(self) -> String {
+        let name = self.encode();
+        ["Key", "Digit"].iter().find_map(|prefix| name.strip_prefix(prefix).map(str::to_string)).unwrap_or(name)
+    }
+}
+
+// Lenkung über den Beschleunigungssensor, `sensitivity` ist der Faktor auf die Neigung in g
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tilt {
+    pub enabled: bool,
+    pub sensitivity: f32,
+    pub deadzone: f32,
+}
+
+impl Default for Tilt {
+    fn default() -> Self {
+        Self { enabled: false, sensitivity: 3.0, deadzone: 0.1 }
+    }
+}
+
+impl Tilt {
+    // Neigt sich das Gerät nach rechts, wird die x-Beschleunigung negativ
+    #[inline]
+    pub fn steer(&self, acceleration_x: f32) -> f32 {
+        quantize_steer(-acceleration_x / GRAVITY * self.sensitivity, self.deadzone)
+    }
+}
+
+// Quelle der Neigung, auf Android der Beschleunigungssensor. Im Hintergrund wird er abgeschaltet, um Akku zu sparen.
+pub trait TiltSensor {
+    // Letzte x-Beschleunigung in m/s²
+    fn acceleration_x(&self) -> f32;
+    fn set_enabled(&mut self, enabled: bool);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Controls {
+    pub bindings: Vec<(Action, Binding)>,
+    pub tilt: Tilt,
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        use Binding::{Key, Pad};
+
+        Self {
+            bindings: vec![
+                (Action::MoveLeft, Key(KeyCode::KeyA)),
+                (Action::MoveLeft, Key(KeyCode::ArrowLeft)),
+                (Action::MoveLeft, Pad(PadButton::DPadLeft)),
+                (Action::MoveRight, Key(KeyCode::KeyD)),
+                (Action::MoveRight, Key(KeyCode::ArrowRight)),
+                (Action::MoveRight, Pad(PadButton::DPadRight)),
+                (Action::Pause, Key(KeyCode::Escape)),
+                (Action::Pause, Key(KeyCode::KeyX)),
+                (Action::Pause, Pad(PadButton::Start)),
+                (Action::ToggleHud, Key(KeyCode::F1)),
+                (Action::ToggleHud, Pad(PadButton::Select)),
+                (Action::Restart, Key(KeyCode::KeyR)),
+                (Action::Restart, Key(KeyCode::Enter)),
+                (Action::Restart, Pad(PadButton::South)),
+                (Action::Highscores, Key(KeyCode::KeyH)),
+                (Action::Highscores, Pad(PadButton::North)),
+                (Action::SaveReplay, Key(KeyCode::F5)),
+                (Action::LoadReplay, Key(KeyCode::F6)),
+                (Action::Step, Key(KeyCode::KeyN)),
+                (Action::ToggleRenderer, Key(KeyCode::KeyM)),
+                (Action::Screenshot, Key(KeyCode::F12)),
+                (Action::Screenshot, Pad(PadButton::West)),
+                (Action::Burst, Key(KeyCode::F11)),
+            ],
+            tilt: Tilt::default(),
+        }
+    }
+}
+
+impl Controls {
+    #[inline]
+    pub fn action(&self, binding: Binding) -> Option<Action> {
+        self.bindings.iter().find(|(_, bound)| *bound == binding).map(|(action, _)| *action)
+    }
+
+    // Ersetzt alle Tasten der Aktion durch `key`, Gamepad-Tasten bleiben erhalten. Die Taste wird anderen Aktionen entzogen.
+    // Tasten, die nicht in KEYS stehen, könnten nicht gespeichert werden und werden abgelehnt.
+    pub fn rebind_key(&mut self, action: Action, key: KeyCode) -> bool {
+        if !KEYS.contains(&key) {
+            return false;
+        }
+        self.bindings.retain(|&(bound_action, binding)| binding != Binding::Key(key) && !(bound_action == action && matches!(binding, Binding::Key(_))));
+        self.bindings.push((action, Binding::Key(key)));
+        true
+    }
+
+    pub fn describe(&self, action: Action) -> String {
+        let keys: Vec<String> = self.bindings.iter().filter(|(bound, binding)| *bound == action && matches!(binding, Binding::Key(_))).map(|(_, binding)| binding.describe()).collect();
+        if keys.is_empty() { "-".to_string() } else { keys.join(", ") }
+    }
+
+    // Format: "action = Taste, Taste, Pad:Knopf", dazu die tilt_*-Einstellungen. Fehlende Einträge behalten ihren Standardwert.
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut controls = Self::default();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = |message: String| io::Error::new(ErrorKind::InvalidData, format!("line {}: {message}", number + 1));
+
+            let Some((name, bindings)) = line.split_once('=') else {
+                return Err(error(format!("expected action = keys, got {line}")));
+            };
+            let (name, bindings) = (name.trim(), bindings.trim());
+            let number = |value: &str| value.parse::<f32>().map_err(|_| error(format!("invalid number {value}")));
+            match name {
+                "tilt_steering" => {
+                    controls.tilt.enabled = match bindings {
+                        "on" => true,
+                        "off" => false,
+                        _ => return Err(error(format!("expected on or off, got {bindings}"))),
+                    };
+                    continue;
+                },
+                "tilt_sensitivity" => {
+                    controls.tilt.sensitivity = number(bindings)?;
+                    if !controls.tilt.sensitivity.is_finite() {
+                        return Err(error(format!("tilt_sensitivity must be a finite number, got {bindings}")));
+                    }
+                    continue;
+                },
+                "tilt_deadzone" => {
+                    let deadzone = number(bindings)?;
+                    if deadzone.is_nan() {
+                        return Err(error(format!("tilt_deadzone must be a number, got {bindings}")));
+                    }
+                    controls.tilt.deadzone = deadzone.clamp(0.0, 0.9);
+                    continue;
+                },
+                _ => (),
+            }
+
+            let Some(action) = Action::ALL.into_iter().find(|action| action.name() == name) else {
+                return Err(error(format!("unknown action {name}")));
+            };
+
+            controls.bindings.retain(|(bound, _)| *bound != action);
+            for binding in bindings.split(',').map(str::trim).filter(|binding| !binding.is_empty()) {
+                let binding = Binding::parse(binding).ok_or_else(|| error(format!("unknown key {binding}")))?;
+                controls.bindings.push((action, binding));
+            }
+        }
+
+        Ok(controls)
+    }
+
+    pub fn encode(&self) -> String {
+        let mut text = String::from("# Vudeljump controls: action = key, key, Pad:Button\n");
+        for action in Action::ALL {
+            let bindings: Vec<String> = self.bindings.iter().filter(|(bound, _)| *bound == action).map(|(_, binding)| binding.encode()).collect();
+            text.push_str(&format!("{} = {}\n", action.name(), bindings.join(", ")));
+        }
+        text.push_str(&format!("tilt_steering = {}\n", if self.tilt.enabled { "on" } else { "off" }));
+        text.push_str(&format!("tilt_sensitivity = {}\n", self.tilt.sensitivity));
+        text.push_str(&format!("tilt_deadzone = {}\n", self.tilt.deadzone));
+        text
+    }
+
+    pub fn load(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).unwrap_or_else(|err| {
+                log::error!("invalid controls config {}: {err}", path.display());
+                Self::default()
+            }),
+            Err(err) => {
+                if err.kind() != ErrorKind::NotFound {
+                    log::error!("could not read controls config {}: {err}", path.display());
+                }
+                Self::default()
+            },
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.encode())
+    }
+}
+
+// Analoge Lenkung von -1 bis 1 mit Totzone, gerastert, damit Replays nicht bei jedem Zittern ein Event aufzeichnen
+pub fn quantize_steer(value: f32, deadzone: f32) -> f32 {
+    const STEPS: f32 = 16.0;
+
+    let value = value.clamp(-1.0, 1.0);
+    if value.abs() < deadzone {
+        return 0.0;
+    }
+    // Nach der Totzone wieder bei 0 beginnen, damit die Lenkung feinfühlig bleibt
+    let scaled = (value.abs() - deadzone) / (1.0 - deadzone);
+    (scaled * STEPS).round() / STEPS * value.signum()
+}
+
+// Tasten, die in der Konfiguration verwendet werden können
+const KEYS: &[KeyCode] = &[
+    KeyCode::KeyA, KeyCode::KeyB, KeyCode::KeyC, KeyCode::KeyD, KeyCode::KeyE, KeyCode::KeyF, KeyCode::KeyG, KeyCode::KeyH, KeyCode::KeyI,
+    KeyCode::KeyJ, KeyCode::KeyK, KeyCode::KeyL, KeyCode::KeyM, KeyCode::KeyN, KeyCode::KeyO, KeyCode::KeyP, KeyCode::KeyQ, KeyCode::KeyR,
+    KeyCode::KeyS, KeyCode::KeyT, KeyCode::KeyU, KeyCode::KeyV, KeyCode::KeyW, KeyCode::KeyX, KeyCode::KeyY, KeyCode::KeyZ,
+    KeyCode::Digit0, KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4, KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6, KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+    KeyCode::ArrowLeft, KeyCode::ArrowRight, KeyCode::ArrowUp, KeyCode::ArrowDown,
+    KeyCode::Space, KeyCode::Enter, KeyCode::Escape, KeyCode::Tab, KeyCode::Backspace,
+    KeyCode::ShiftLeft, KeyCode::ShiftRight, KeyCode::ControlLeft, KeyCode::ControlRight, KeyCode::AltLeft, KeyCode::AltRight,
+    KeyCode::Comma, KeyCode::Period, KeyCode::Slash, KeyCode::Semicolon, KeyCode::Quote, KeyCode::BracketLeft, KeyCode::BracketRight, KeyCode::Minus, KeyCode::Equal,
+    KeyCode::Backquote, KeyCode::Backslash, KeyCode::IntlBackslash, KeyCode::CapsLock,
+    KeyCode::Insert, KeyCode::Delete, KeyCode::Home, KeyCode::End, KeyCode::PageUp, KeyCode::PageDown,
+    KeyCode::Numpad0, KeyCode::Numpad1, KeyCode::Numpad2, KeyCode::Numpad3, KeyCode::Numpad4, KeyCode::Numpad5, KeyCode::Numpad6, KeyCode::Numpad7, KeyCode::Numpad8, KeyCode::Numpad9,
+    KeyCode::NumpadAdd, KeyCode::NumpadSubtract, KeyCode::NumpadMultiply, KeyCode::NumpadDivide, KeyCode::NumpadDecimal, KeyCode::NumpadEnter,
+];
+
+#[test]
+fn controls_roundtrip_and_rebind() {
+    let mut controls = Controls::default();
+    assert_eq!(Controls::parse(&controls.encode()).unwrap(), controls);
+
+    assert!(controls.rebind_key(Action::Restart, KeyCode::KeyA));
+    assert_eq!(controls.action(Binding::Key(KeyCode::KeyA)), Some(Action::Restart));
+    assert_eq!(controls.action(Binding::Key(KeyCode::Enter)), None);
+    assert_eq!(controls.action(Binding::Pad(PadButton::South)), Some(Action::Restart));
+    assert_eq!(controls.describe(Action::MoveLeft), "ArrowLeft");
+
+    let custom = Controls::parse("pause = KeyP, Pad:East\n").unwrap();
+    assert_eq!(custom.action(Binding::Key(KeyCode::KeyP)), Some(Action::Pause));
+    assert_eq!(custom.action(Binding::Key(KeyCode::Escape)), None);
+    assert_eq!(custom.action(Binding::Key(KeyCode::KeyA)), Some(Action::MoveLeft));
+
+    assert!(Controls::parse("jump = Space\n").is_err());
+    assert!(Controls::parse("tilt_sensitivity = nan\n").is_err());
+    assert!(Controls::parse("tilt_sensitivity = inf\n").is_err());
+    assert!(Controls::parse("tilt_deadzone = NaN\n").is_err());
+    assert!(Controls::parse("pause = Pad:Trigger\n").is_err());
+
+    let tilt = Controls::parse("tilt_steering = on\ntilt_sensitivity = 2\n").unwrap().tilt;
+    assert!(tilt.enabled);
+    assert_eq!(tilt.steer(-9.81), 1.0);
+    assert_eq!(tilt.steer(0.3), 0.0);
+}
+
+#[test]
+fn steer_values_are_quantized() {
+    assert_eq!(quantize_steer(0.1, 0.2), 0.0);
+    assert_eq!(quantize_steer(-0.19, 0.2), 0.0);
+    assert_eq!(quantize_steer(1.0, 0.2), 1.0);
+    assert_eq!(quantize_steer(-3.0, 0.2), -1.0);
+    assert_eq!(quantize_steer(0.6, 0.2), 0.5);
+}
+
+#[test]
+fn rebinding_keeps_the_config_readable() {
+    let mut controls = Controls::default();
+    assert!(controls.rebind_key(Action::Pause, KeyCode::Home));
+    assert!(!controls.rebind_key(Action::MoveLeft, KeyCode::MediaPlayPause));
+    assert_eq!(controls.action(Binding::Key(KeyCode::MediaPlayPause)), None);
+    let parsed = Controls::parse(&controls.encode()).unwrap();
+    assert_eq!(parsed.action(Binding::Key(KeyCode::Home)), Some(Action::Pause));
+    assert_eq!(parsed.encode(), controls.encode());
+
+    // Jede Taste, die angenommen wird, muss sich auch wieder lesen lassen
+    for &key in KEYS {
+        assert_eq!(Binding::parse(&Binding::Key(key).encode()), Some(Binding::Key(key)));
+    }
+}
@@ -0,0 +1,241 @@
+use std::{fs, io::{self, ErrorKind}, path::Path};
+
+pub const DIFFICULTY_FILE: &str = "difficulty.cfg";
+// Index von "normal" in den eingebauten Presets, wird für alte Replays ohne Schwierigkeit verwendet
+pub const DEFAULT_DIFFICULTY: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Physics {
+    pub gravity: f32,
+    pub jump_velocity: f32,
+    pub move_speed: f32,
+    pub spring_strength: f32,
+    pub jetpack_speed: f32,
+}
+
+impl Default for Physics {
+    fn default() -> Self {
+        Self { gravity: 600.0, jump_velocity: 460.0, move_speed: 500.0, spring_strength: 800.0, jetpack_speed: 700.0 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difficulty {
+    pub name: String,
+    pub physics: Physics,
+    // hardness = sqrt(score) / hardness_scale
+    pub hardness_scale: f32,
+    pub row_spacing: f32,
+    // Reihe wird ausgelassen, wenn gen_range(0..skip_range) <= hardness * Dichte
+    pub skip_range: f32,
+    // Beweger, wenn gen_range(0..mover_range) <= hardness (höchstens mover_max_hardness)
+    pub mover_range: f32,
+    pub mover_max_hardness: f32,
+    pub vertical_share: f32,
+    // Zerbrechliche, verschwindende und Einweg-Plattformen teilen sich special_range
+    pub special_range: f32,
+    pub special_max_hardness: f32,
+    // Item, wenn gen_range(1..item_range) == 1 + hardness (höchstens item_max_hardness)
+    pub item_range: f32,
+    pub item_max_hardness: f32,
+    pub enemy_rate: f32,
+    pub enemy_max_chance: f32,
+}
+
+impl Difficulty {
+    pub fn normal() -> Self {
+        Self {
+            name: "normal".to_string(),
+            physics: Physics::default(),
+            hardness_scale: 60.0,
+            row_spacing: 30.0,
+            skip_range: 1.3,
+            mover_range: 9.0,
+            mover_max_hardness: 5.0,
+            vertical_share: 0.3,
+            special_range: 12.0,
+            special_max_hardness: 4.0,
+            item_range: 20.0,
+            item_max_hardness: 3.0,
+            enemy_rate: 0.1,
+            enemy_max_chance: 0.2,
+        }
+    }
+
+    pub fn easy() -> Self {
+        Self {
+            name: "easy".to_string(),
+            physics: Physics { gravity: 560.0, ..Physics::default() },
+            hardness_scale: 90.0,
+            skip_range: 1.6,
+            mover_range: 12.0,
+            item_range: 14.0,
+            enemy_rate: 0.05,
+            enemy_max_chance: 0.1,
+            ..Self::normal()
+        }
+    }
+
+    pub fn hard() -> Self {
+        Self {
+            name: "hard".to_string(),
+            physics: Physics { gravity: 680.0, jump_velocity: 490.0, move_speed: 540.0, ..Physics::default() },
+            hardness_scale: 40.0,
+            skip_range: 1.1,
+            mover_range: 7.0,
+            item_range: 26.0,
+            enemy_rate: 0.15,
+            enemy_max_chance: 0.3,
+            ..Self::normal()
+        }
+    }
+
+    #[inline]
+    pub fn hardness(&self, score: u32) -> f32 {
+        (score as f32).sqrt() / self.hardness_scale
+    }
+
+    // Alle einstellbaren Werte mit ihrem Namen in der Konfigurationsdatei
+    fn fields_mut(&mut self) -> [(&'static str, &mut f32); 17] {
+        [
+            ("gravity", &mut self.physics.gravity),
+            ("jump_velocity", &mut self.physics.jump_velocity),
+            ("move_speed", &mut self.physics.move_speed),
+            ("spring_strength", &mut self.physics.spring_strength),
+            ("jetpack_speed", &mut self.physics.jetpack_speed),
+            ("hardness_scale", &mut self.hardness_scale),
+            ("row_spacing", &mut self.row_spacing),
+            ("skip_range", &mut self.skip_range),
+            ("mover_range", &mut self.mover_range),
+            ("mover_max_hardness", &mut self.mover_max_hardness),
+            ("vertical_share", &mut self.vertical_share),
+            ("special_range", &mut self.special_range),
+            ("special_max_hardness", &mut self.special_max_hardness),
+            ("item_range", &mut self.item_range),
+            ("item_max_hardness", &mut self.item_max_hardness),
+            ("enemy_rate", &mut self.enemy_rate),
+            ("enemy_max_chance", &mut self.enemy_max_chance),
+        ]
+    }
+}
+
+// Werte, mit denen der Generator in gen_range oder die Sprungberechnung durch null teilen würde, werden abgelehnt
+fn check_field(key: &str, value: f32) -> Result<(), &'static str> {
+    let message = match key {
+        _ if !value.is_finite() => "must be a finite number",
+        "item_range" if value < 2.0 => "must be at least 2",
+        "vertical_share" | "enemy_max_chance" if !(0.0..=1.0).contains(&value) => "must be between 0 and 1",
+        "gravity" | "jump_velocity" | "move_speed" | "hardness_scale" | "row_spacing" | "skip_range" | "mover_range" | "special_range" if value <= 0.0 => "must be greater than 0",
+        _ if value < 0.0 => "must not be negative",
+        _ => return Ok(()),
+    };
+    Err(message)
+}
+
+pub fn default_presets() -> Vec<Difficulty> {
+    vec![Difficulty::easy(), Difficulty::normal(), Difficulty::hard()]
+}
+
+// Format: "[name]" beginnt ein Preset, danach "key = value". Fehlende Werte kommen aus dem gleichnamigen eingebauten Preset oder aus "normal".
+pub fn parse_presets(text: &str) -> io::Result<Vec<Difficulty>> {
+    let mut presets: Vec<Difficulty> = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            let name = name.trim();
+            let base = default_presets().into_iter().find(|preset| preset.name == name).unwrap_or_else(Difficulty::normal);
+            presets.push(Difficulty { name: name.to_string(), ..base });
+            continue;
+        }
+
+        let error = |message: String| io::Error::new(ErrorKind::InvalidData, format!("line {}: {message}", number + 1));
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(error(format!("expected key = value, got {line}")));
+        };
+        let Some(preset) = presets.last_mut() else {
+            return Err(error("value outside of a [preset] section".to_string()));
+        };
+        let value: f32 = value.trim().parse().map_err(|err| error(format!("invalid value for {}: {err}", key.trim())))?;
+
+        match preset.fields_mut().into_iter().find(|(name, _)| *name == key.trim()) {
+            Some((_, field)) => *field = value,
+            None => return Err(error(format!("unknown key {}", key.trim()))),
+        }
+    }
+
+    if presets.is_empty() {
+        return Err(io::Error::new(ErrorKind::InvalidData, "no difficulty presets defined"));
+    }
+
+    for preset in &presets {
+        for (key, value) in preset.clone().fields_mut() {
+            check_field(key, *value).map_err(|message| io::Error::new(ErrorKind::InvalidData, format!("preset {}: {key} {message}, got {value}", preset.name)))?;
+        }
+    }
+
+    Ok(presets)
+}
+
+pub fn encode_presets(presets: &[Difficulty]) -> String {
+    let mut text = String::from("# Vudeljump difficulty presets, edit and restart the game to apply\n");
+    for preset in presets {
+        text.push_str(&format!("\n[{}]\n", preset.name));
+        for (key, value) in preset.clone().fields_mut() {
+            text.push_str(&format!("{key} = {value}\n"));
+        }
+    }
+    text
+}
+
+// Eine fehlende Datei wird mit den eingebauten Presets angelegt, damit sie bearbeitet werden kann
+pub fn load_presets(path: &Path) -> Vec<Difficulty> {
+    match fs::read_to_string(path) {
+        Ok(text) => parse_presets(&text).unwrap_or_else(|err| {
+            log::error!("invalid difficulty config {}: {err}", path.display());
+            default_presets()
+        }),
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            let presets = default_presets();
+            if let Err(err) = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::write(path, encode_presets(&presets))) {
+                log::error!("could not write difficulty config: {err}");
+            }
+            presets
+        },
+        Err(err) => {
+            log::error!("could not read difficulty config {}: {err}", path.display());
+            default_presets()
+        },
+    }
+}
+
+#[test]
+fn presets_roundtrip_and_fill_missing_values() {
+    let presets = default_presets();
+    assert_eq!(parse_presets(&encode_presets(&presets)).unwrap(), presets);
+
+    let custom = parse_presets("[hard]\ngravity = 900 # schwerer\n\n[custom]\nrow_spacing = 25\n").unwrap();
+    assert_eq!(custom[0].physics.gravity, 900.0);
+    assert_eq!(custom[0].hardness_scale, Difficulty::hard().hardness_scale);
+    assert_eq!(custom[1].name, "custom");
+    assert_eq!(custom[1].row_spacing, 25.0);
+    assert_eq!(custom[1].skip_range, Difficulty::normal().skip_range);
+
+    assert!(parse_presets("[easy]\nspeed = 3\n").is_err());
+    assert!(parse_presets("gravity = 3\n").is_err());
+}
+
+#[test]
+fn presets_with_unusable_values_are_rejected() {
+    for (key, value) in [("gravity", "0"), ("jump_velocity", "-1"), ("move_speed", "0"), ("hardness_scale", "0"), ("row_spacing", "0"), ("skip_range", "0"), ("mover_range", "0"), ("special_range", "0"),
+                         ("item_range", "1"), ("vertical_share", "1.5"), ("enemy_max_chance", "-0.1"), ("mover_max_hardness", "-1"), ("enemy_rate", "NaN"), ("spring_strength", "inf")] {
+        let err = parse_presets(&format!("[custom]\n{key} = {value}\n")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().contains("custom") && err.to_string().contains(key), "{err}");
+    }
+}
@@ -0,0 +1,72 @@
+use cgmath::Vector2;
+
+use crate::graphic::{atlas, Layer, SpriteBatch};
+
+use super::playfield::WIDTH;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnemyKind {
+    Stationary,
+    Patrol,
+    Hover,
+}
+
+#[derive(Debug)]
+pub struct Enemy {
+    pub pos: Vector2<f32>,
+    pub prev_pos: Vector2<f32>,
+    pub size: Vector2<f32>,
+    pub kind: EnemyKind,
+    pub origin: Vector2<f32>,
+    pub direction: f32,
+}
+
+impl Enemy {
+    pub const SIZE: Vector2<f32> = Vector2 { x: 30.0, y: 30.0 };
+    const PATROL_SPEED: f32 = 80.0;
+    const PATROL_MIN_X: f32 = 0.0;
+    const PATROL_MAX_X: f32 = WIDTH - 30.0;
+    const HOVER_SPEED: f32 = 40.0;
+    const HOVER_RANGE: f32 = 40.0;
+
+    pub const fn new(pos: Vector2<f32>, kind: EnemyKind) -> Self {
+        Self { pos, prev_pos: pos, size: Self::SIZE, kind, origin: pos, direction: 1.0 }
+    }
+
+    #[inline]
+    pub fn update(&mut self, delta_time: f32) {
+        match self.kind {
+            EnemyKind::Stationary => (),
+            EnemyKind::Patrol => {
+                self.pos.x += Self::PATROL_SPEED * self.direction * delta_time;
+
+                if self.pos.x >= Self::PATROL_MAX_X {
+                    self.direction = -1.0;
+                } else if self.pos.x <= Self::PATROL_MIN_X {
+                    self.direction = 1.0;
+                }
+            },
+            EnemyKind::Hover => {
+                self.pos.y += Self::HOVER_SPEED * self.direction * delta_time;
+
+                if self.pos.y >= self.origin.y + Self::HOVER_RANGE {
+                    self.direction = -1.0;
+                } else if self.pos.y <= self.origin.y {
+                    self.direction = 1.0;
+                }
+            },
+        }
+    }
+
+    pub fn draw(&self, alpha: f32, batch: &mut SpriteBatch) {
+        let mut pos = self.prev_pos + (self.pos - self.prev_pos) * alpha;
+        pos.y *= -1.0;
+
+        let sprite = match self.kind {
+            EnemyKind::Stationary => "enemy_stationary",
+            EnemyKind::Patrol => "enemy_patrol",
+            EnemyKind::Hover => "enemy_hover",
+        };
+        batch.draw(Layer::Enemies, pos, self.size, atlas::sprite(sprite));
+    }
+}
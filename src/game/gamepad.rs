@@ -0,0 +1,91 @@
+use super::controls::PadButton;
+
+#[cfg(not(target_os = "android"))]
+const DEADZONE: f32 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PadEvent {
+    Button(PadButton, bool),
+    Steer(f32),
+}
+
+// Auf Android gibt es kein gilrs, dort bleibt die Liste der Events leer
+pub struct Gamepads {
+    #[cfg(not(target_os = "android"))]
+    gilrs: Option<gilrs::Gilrs>,
+    #[cfg(not(target_os = "android"))]
+    steer: f32,
+}
+
+impl Gamepads {
+    pub fn create() -> Self {
+        #[cfg(not(target_os = "android"))]
+        let gilrs = gilrs::Gilrs::new().map_err(|err| log::error!("gamepads unavailable: {err}")).ok();
+
+        Self {
+            #[cfg(not(target_os = "android"))]
+            gilrs,
+            #[cfg(not(target_os = "android"))]
+            steer: 0.0,
+        }
+    }
+
+    #[cfg(not(target_os = "android"))]
+    pub fn poll(&mut self) -> Vec<PadEvent> {
+        use gilrs::{Axis, EventType};
+        use super::controls::quantize_steer;
+
+        let mut events = Vec::new();
+        let Some(gilrs) = &mut self.gilrs else {
+            return events;
+        };
+
+        while let Some(event) = gilrs.next_event() {
+            match event.event {
+                EventType::ButtonPressed(button, _) => events.extend(pad_button(button).map(|button| PadEvent::Button(button, true))),
+                EventType::ButtonReleased(button, _) => events.extend(pad_button(button).map(|button| PadEvent::Button(button, false))),
+                EventType::AxisChanged(Axis::LeftStickX, value, _) => {
+                    let steer = quantize_steer(value, DEADZONE);
+                    if steer != self.steer {
+                        self.steer = steer;
+                        events.push(PadEvent::Steer(steer));
+                    }
+                },
+                // Ohne Stick-Nullstellung würde der Spieler nach dem Trennen weiterlaufen
+                EventType::Disconnected if self.steer != 0.0 => {
+                    self.steer = 0.0;
+                    events.push(PadEvent::Steer(0.0));
+                },
+                _ => (),
+            }
+        }
+
+        events
+    }
+
+    #[cfg(target_os = "android")]
+    pub fn poll(&mut self) -> Vec<PadEvent> {
+        Vec::new()
+    }
+}
+
+#[cfg(not(target_os = "android"))]
+fn pad_button(button: gilrs::Button) -> Option<PadButton> {
+    use gilrs::Button;
+
+    Some(match button {
+        Button::South => PadButton::South,
+        Button::East => PadButton::East,
+        Button::North => PadButton::North,
+        Button::West => PadButton::West,
+        Button::LeftTrigger => PadButton::LeftTrigger,
+        Button::RightTrigger => PadButton::RightTrigger,
+        Button::Select => PadButton::Select,
+        Button::Start => PadButton::Start,
+        Button::DPadUp => PadButton::DPadUp,
+        Button::DPadDown => PadButton::DPadDown,
+        Button::DPadLeft => PadButton::DPadLeft,
+        Button::DPadRight => PadButton::DPadRight,
+        _ => return None,
+    })
+}
@@ -0,0 +1,145 @@
+use std::{fs, io::{self, ErrorKind}, path::Path, time::{SystemTime, UNIX_EPOCH}};
+
+use log::warn;
+
+pub const HIGHSCORE_FILE: &str = "highscores.vjs";
+pub const MAX_ENTRIES: usize = 10;
+
+const MAGIC: &[u8; 4] = b"VJHS";
+const VERSION: u8 = 1;
+const ENTRY_SIZE: usize = 4 + 8 + 8 + 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreEntry {
+    pub score: u32,
+    // Sekunden seit 1970-01-01 UTC
+    pub date: u64,
+    pub seed: u64,
+    // Dauer des Laufs in Sekunden Simulationszeit
+    pub duration: f32,
+}
+
+impl ScoreEntry {
+    pub fn new(score: u32, seed: u64, duration: f32) -> Self {
+        let date = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+        Self { score, date, seed, duration }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct HighScores {
+    pub entries: Vec<ScoreEntry>,
+}
+
+impl HighScores {
+    // Fügt den Eintrag ein, falls er in die Tabelle kommt, und gibt den Rang zurück
+    pub fn submit(&mut self, entry: ScoreEntry) -> Option<usize> {
+        let rank = self.entries.iter().position(|other| entry.score > other.score).unwrap_or(self.entries.len());
+        if rank >= MAX_ENTRIES {
+            return None;
+        }
+
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_ENTRIES);
+        Some(rank)
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(9 + self.entries.len() * ENTRY_SIZE);
+        buf.extend_from_slice(MAGIC);
+        buf.push(VERSION);
+        buf.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+
+        for entry in &self.entries {
+            buf.extend_from_slice(&entry.score.to_le_bytes());
+            buf.extend_from_slice(&entry.date.to_le_bytes());
+            buf.extend_from_slice(&entry.seed.to_le_bytes());
+            buf.extend_from_slice(&entry.duration.to_le_bytes());
+        }
+
+        buf
+    }
+
+    pub fn decode(data: &[u8]) -> io::Result<Self> {
+        if data.len() < 9 || &data[0..4] != MAGIC {
+            return Err(io::Error::new(ErrorKind::InvalidData, "not a highscore file"));
+        }
+        if data[4] != VERSION {
+            return Err(io::Error::new(ErrorKind::InvalidData, format!("unsupported highscore version {}", data[4])));
+        }
+
+        let count = u32::from_le_bytes(data[5..9].try_into().unwrap()) as usize;
+        let body = &data[9..];
+        let Some(size) = count.checked_mul(ENTRY_SIZE) else {
+            return Err(io::Error::new(ErrorKind::InvalidData, format!("invalid highscore count {count}")));
+        };
+        if body.len() < size {
+            return Err(io::Error::from(ErrorKind::UnexpectedEof));
+        }
+
+        let entries = body.chunks_exact(ENTRY_SIZE).take(count.min(MAX_ENTRIES)).map(|chunk| ScoreEntry {
+            score: u32::from_le_bytes(chunk[0..4].try_into().unwrap()),
+            date: u64::from_le_bytes(chunk[4..12].try_into().unwrap()),
+            seed: u64::from_le_bytes(chunk[12..20].try_into().unwrap()),
+            duration: f32::from_le_bytes(chunk[20..24].try_into().unwrap()),
+        }).collect();
+
+        Ok(Self { entries })
+    }
+
+    pub fn load(path: &Path) -> Self {
+        match fs::read(path).and_then(|data| Self::decode(&data)) {
+            Ok(scores) => scores,
+            Err(err) => {
+                if err.kind() != ErrorKind::NotFound {
+                    warn!("could not load highscores from {}: {err}", path.display());
+                }
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.encode())
+    }
+}
+
+// Datum im Format JJJJ-MM-TT (UTC)
+pub fn format_date(unix_secs: u64) -> String {
+    let days = (unix_secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[test]
+fn highscores_roundtrip_and_ranking() {
+    let mut scores = HighScores::default();
+    for score in 0..15 {
+        scores.submit(ScoreEntry { score: score * 100, date: 1_760_745_600, seed: score as u64, duration: score as f32 });
+    }
+    assert_eq!(scores.entries.len(), MAX_ENTRIES);
+    assert_eq!(scores.entries[0].score, 1400);
+    assert_eq!(scores.submit(ScoreEntry { score: 0, date: 0, seed: 0, duration: 0.0 }), None);
+
+    let decoded = HighScores::decode(&scores.encode()).unwrap();
+    assert_eq!(decoded.entries, scores.entries);
+    assert_eq!(format_date(1_760_745_600), "2025-10-18");
+}
+
+#[test]
+fn truncated_highscores_are_rejected() {
+    let mut data = HighScores::default().encode();
+    data[5..9].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(HighScores::decode(&data).is_err());
+}
@@ -1,22 +1,120 @@
 use cgmath::Vector2;
 
-use crate::graphic::InstanceData;
+use crate::graphic::{atlas, Layer, SpriteBatch};
+
+use super::{animation::{Animation, Clip}, Player};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ItemKind {
+    Spring,
+    Jetpack,
+    Shield,
+    Multiplier,
+}
+
+impl ItemKind {
+    // Wirkungsdauer in Sekunden, 0 für Items mit sofortiger Wirkung
+    pub const fn duration(self) -> f32 {
+        match self {
+            Self::Spring => 0.0,
+            Self::Jetpack => 2.5,
+            Self::Shield => 10.0,
+            Self::Multiplier => 8.0,
+        }
+    }
+
+    // Name im Manifest textures/texture.atlas
+    const fn sprite(self) -> &'static str {
+        match self {
+            Self::Spring => "item_spring",
+            Self::Jetpack => "item_jetpack",
+            Self::Shield => "item_shield",
+            Self::Multiplier => "item_multiplier",
+        }
+    }
+}
+
+// Verbleibende Wirkungsdauer der aktiven Items in Sekunden
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Effects {
+    pub jetpack: f32,
+    pub shield: f32,
+    pub multiplier: f32,
+}
+
+impl Effects {
+    pub const SCORE_MULTIPLIER: f32 = 2.0;
+
+    #[inline]
+    pub fn update(&mut self, delta_time: f32) {
+        self.jetpack = (self.jetpack - delta_time).max(0.0);
+        self.shield = (self.shield - delta_time).max(0.0);
+        self.multiplier = (self.multiplier - delta_time).max(0.0);
+    }
+
+    #[inline]
+    pub fn score_multiplier(&self) -> f32 {
+        if self.multiplier > 0.0 { Self::SCORE_MULTIPLIER } else { 1.0 }
+    }
+
+    // Text für das HUD, leer wenn kein Effekt aktiv ist
+    pub fn describe(&self) -> String {
+        let mut text = String::new();
+        for (name, time) in [("Jetpack", self.jetpack), ("Shield", self.shield), ("x2", self.multiplier)] {
+            if time > 0.0 {
+                if !text.is_empty() {
+                    text.push_str("  ");
+                }
+                text.push_str(&format!("{name} {}", time.ceil() as u32));
+            }
+        }
+        text
+    }
+}
 
 #[derive(Debug)]
 pub struct Item {
     pub pos: Vector2<f32>,
     pub size: Vector2<f32>,
-    pub typ: u8,
+    pub kind: ItemKind,
 }
 
 impl Item {
-    pub fn get_instance(&self) -> InstanceData {
+    pub fn apply(&self, player: &mut Player) {
+        match self.kind {
+            ItemKind::Spring => player.jump_with_strenght(player.physics.spring_strength),
+            ItemKind::Jetpack => player.effects.jetpack = self.kind.duration(),
+            ItemKind::Shield => player.effects.shield = self.kind.duration(),
+            ItemKind::Multiplier => player.effects.multiplier = self.kind.duration(),
+        }
+    }
+
+    // `offset` verschiebt das Item mit der interpolierten Position seiner Plattform
+    pub fn draw(&self, offset: Vector2<f32>, batch: &mut SpriteBatch) {
+        let mut pos = self.pos + offset;
+        pos.y *= -1.0;
+
+        batch.draw(Layer::Items, pos, self.size, atlas::sprite(self.kind.sprite()));
+    }
+}
+
+// Kurzes Funkeln an der Stelle eines aufgesammelten Items
+#[derive(Debug)]
+pub struct Pickup {
+    pub pos: Vector2<f32>,
+    pub size: Vector2<f32>,
+    pub animation: Animation,
+}
+
+impl Pickup {
+    pub const fn new(item: &Item) -> Self {
+        Self { pos: item.pos, size: item.size, animation: Animation::new(&Clip::ITEM_PICKUP) }
+    }
+
+    pub fn draw(&self, batch: &mut SpriteBatch) {
         let mut pos = self.pos;
         pos.y *= -1.0;
 
-        //((high as u32) << 16) | (low as u32)
-        let uv_start = 160;
-        let uv_end = (7 << 16) | 9; 
-        InstanceData::new(pos, self.size, 0, uv_start, uv_end )
+        batch.draw(Layer::Particles, pos, self.size, self.animation.sprite());
     }
-}
\ No newline at end of file
+}
@@ -4,8 +4,21 @@ pub mod world;
 pub mod player;
 pub mod platform;
 pub mod item;
+pub mod enemy;
+pub mod replay;
+pub mod reachability;
+pub mod highscore;
+pub mod storage;
+pub mod difficulty;
+pub mod controls;
+pub mod gamepad;
+pub mod playfield;
+pub mod camera;
+pub mod animation;
+pub mod background;
 
 pub use item::Item;
+pub use enemy::Enemy;
 pub use world::World;
 pub use player::Player;
 pub use platform::Platform;
@@ -1,60 +1,203 @@
 use cgmath::Vector2;
 
-use crate::graphic::InstanceData;
+use crate::graphic::{atlas, Layer, SpriteBatch};
 
-use super::Item;
+use super::{animation::{Animation, Clip}, playfield::WIDTH, Item};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlatformKind {
+    Static,
+    Horizontal,
+    Vertical,
+    // Zerbricht bei der Landung, ohne den Spieler abspringen zu lassen
+    Breakable,
+    // Verschwindet kurz nach der ersten Landung
+    Vanishing,
+    // Verschwindet direkt nach dem ersten Absprung
+    OneShot,
+}
+
+impl PlatformKind {
+    // Kann der Spieler von dieser Plattform abspringen?
+    #[inline]
+    pub const fn is_solid(self) -> bool {
+        !matches!(self, Self::Breakable)
+    }
+}
 
 #[derive(Debug)]
 pub struct Platform {
     pub pos: Vector2<f32>,
+    // Position zu Beginn des Ticks, zum Zeichnen zwischen zwei Ticks
+    pub prev_pos: Vector2<f32>,
     pub size: Vector2<f32>,
-    pub id: u8,
+    pub kind: PlatformKind,
     pub direction: f32,
     pub item: Option<Item>,
+    pub origin: Vector2<f32>,
+    pub vanish_timer: Option<f32>,
+    pub broken: bool,
+    pub gone: bool,
+    // Läuft nur, solange die Plattform zerbricht
+    pub animation: Option<Animation>,
 }
 
 impl Platform {
-    pub const fn new(pos: Vector2<f32>, size: Vector2<f32>, id: u8, item: Option<Item>) -> Self {
-        Self { pos, size, id, direction: 1.0, item: item }
+    // Grenzen für die Bewegung
+    pub const MOVER_MIN_X: f32 = 10.0;
+    pub const MOVER_MAX_X: f32 = WIDTH - 70.0;
+    pub const VERTICAL_RANGE: f32 = 60.0;
+    const MOVER_SPEED: f32 = 100.0;
+    const VERTICAL_SPEED: f32 = 60.0;
+    const VANISH_TIME: f32 = 1.5;
+    // Die letzte halbe Sekunde vor dem Verschwinden wird geblinkt
+    const BLINK_TIME: f32 = 0.5;
+    const FALL_SPEED: f32 = 300.0;
+
+    pub const fn new(pos: Vector2<f32>, size: Vector2<f32>, kind: PlatformKind, item: Option<Item>) -> Self {
+        Self { pos, prev_pos: pos, size, kind, direction: 1.0, item, origin: pos, vanish_timer: None, broken: false, gone: false, animation: None }
     }
 
     #[inline]
     pub fn update(&mut self, delta_time: f32) {
-        if self.id != 1 { return };
+        if self.broken {
+            self.pos.y -= Self::FALL_SPEED * delta_time;
+            if let Some(animation) = &mut self.animation {
+                animation.update(delta_time);
+            }
+            return;
+        }
+
+        if let Some(timer) = &mut self.vanish_timer {
+            *timer -= delta_time;
+            if *timer <= 0.0 {
+                self.gone = true;
+            }
+        }
+
+        match self.kind {
+            PlatformKind::Horizontal => {
+                let dx = Self::MOVER_SPEED * self.direction * delta_time;
+                self.pos.x += dx;
+
+                if let Some(item) = &mut self.item {
+                    item.pos.x += dx;
+                }
+
+                if self.pos.x >= Self::MOVER_MAX_X {
+                    self.direction = -1.0; // Wechsle die Richtung nach links
+                } else if self.pos.x <= Self::MOVER_MIN_X {
+                    self.direction = 1.0;  // Wechsle die Richtung nach rechts
+                }
+            },
+            PlatformKind::Vertical => {
+                let dy = Self::VERTICAL_SPEED * self.direction * delta_time;
+                self.pos.y += dy;
+
+                if let Some(item) = &mut self.item {
+                    item.pos.y += dy;
+                }
+
+                if self.pos.y >= self.origin.y + Self::VERTICAL_RANGE {
+                    self.direction = -1.0;
+                } else if self.pos.y <= self.origin.y {
+                    self.direction = 1.0;
+                }
+            },
+            _ => (),
+        }
+    }
 
-        self.pos.x += 100.0 * self.direction * delta_time;
+    // Wird aufgerufen, wenn der Spieler auf der Plattform landet. Gibt zurück, ob er abspringen darf.
+    pub fn land(&mut self) -> bool {
+        match self.kind {
+            PlatformKind::Breakable => {
+                self.broken = true;
+                self.item = None;
+                self.animation = Some(Animation::new(&Clip::PLATFORM_BREAK));
+                false
+            },
+            PlatformKind::Vanishing => {
+                self.vanish_timer.get_or_insert(Self::VANISH_TIME);
+                true
+            },
+            PlatformKind::OneShot => {
+                self.gone = true;
+                true
+            },
+            _ => true,
+        }
+    }
 
-        if let Some(item) = &mut self.item {
-            item.pos.x += 100.0 * self.direction * delta_time;
+    // Horizontaler Bereich, den die Plattform einnehmen kann
+    pub fn span(&self) -> (f32, f32) {
+        if self.kind == PlatformKind::Horizontal {
+            (Self::MOVER_MIN_X, Self::MOVER_MAX_X + self.size.x)
+        } else {
+            (self.pos.x, self.pos.x + self.size.x)
         }
+    }
 
-        // Grenzen für die Bewegung
-        if self.pos.x >= 330.0 {
-            self.direction = -1.0; // Wechsle die Richtung nach links
-        } else if self.pos.x <= 10.0 {
-            self.direction = 1.0;  // Wechsle die Richtung nach rechts
+    // Niedrigste und höchste Oberkante, die die Plattform erreichen kann
+    pub fn vertical_span(&self) -> (f32, f32) {
+        if self.kind == PlatformKind::Vertical {
+            (self.origin.y, self.origin.y + Self::VERTICAL_RANGE)
+        } else {
+            (self.pos.y, self.pos.y)
         }
     }
 
-    pub fn get_instance(&self, vec: &mut Vec<InstanceData>) {
-        let mut pos = self.pos;
+    pub fn draw(&self, alpha: f32, batch: &mut SpriteBatch) {
+        // Verschwundene Plattformen bleiben bis zum Entfernen unter dem Bildschirm in der Liste, damit die Erreichbarkeit prüfbar bleibt
+        if self.gone {
+            return;
+        }
+
+        // Das Item fährt mit der Plattform mit und wird um dasselbe Stück verschoben
+        let offset = (self.prev_pos - self.pos) * (1.0 - alpha);
+        let mut pos = self.pos + offset;
         pos.y *= -1.0;
 
-        //((high as u32) << 16) | (low as u32)
-        let uv_start;
-        let uv_end;
+        // Kurz vor dem Verschwinden blinken
+        let blink = self.vanish_timer.is_some_and(|timer| timer < Self::BLINK_TIME && (timer * 10.0) as i32 % 2 == 0);
 
-        if self.id == 0 {
-            uv_start = 0;
-            uv_end = (6 << 16) | 30; 
-        } else {
-            uv_start = 8 << 16;
-            uv_end = (6 << 16) | 30; 
+        let sprite = match self.kind {
+            PlatformKind::Static => "platform",
+            PlatformKind::Horizontal => "platform_horizontal",
+            PlatformKind::Vertical => "platform_vertical",
+            PlatformKind::Breakable => "platform_breakable",
+            PlatformKind::Vanishing => "platform_vanishing",
+            PlatformKind::OneShot => "platform_oneshot",
+        };
+        let sprite = self.animation.map_or(sprite, |animation| animation.frame().sprite);
+
+        if !blink {
+            batch.draw(Layer::Platforms, pos, self.size, atlas::sprite(sprite));
         }
-        vec.push(InstanceData::new(pos, self.size, 0, uv_start, uv_end ));
 
         if let Some(item) = &self.item {
-            vec.push(item.get_instance());
+            item.draw(offset, batch);
         }
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn moving_platforms_are_drawn_between_ticks() {
+    use super::item::ItemKind;
+    use crate::graphic::InstanceData;
+
+    let item = Item { pos: Vector2 { x: 120.0, y: 215.0 }, size: Vector2 { x: 20.0, y: 20.0 }, kind: ItemKind::Shield };
+    let mut platform = Platform::new(Vector2 { x: 100.0, y: 200.0 }, Vector2 { x: 60.0, y: 12.0 }, PlatformKind::Horizontal, Some(item));
+    platform.prev_pos = platform.pos;
+    platform.update(0.1);
+
+    let mut batch = SpriteBatch::default();
+    platform.draw(0.5, &mut batch);
+
+    // Halber Weg der 10 Einheiten dieses Ticks, für Plattform und Item
+    let expected = [
+        InstanceData::from_sprite(Vector2 { x: 105.0, y: -200.0 }, platform.size, atlas::sprite("platform_horizontal")),
+        InstanceData::from_sprite(Vector2 { x: 125.0, y: -215.0 }, Vector2 { x: 20.0, y: 20.0 }, atlas::sprite("item_shield")),
+    ];
+    assert!(batch.layers().flatten().eq(expected.iter()));
+}
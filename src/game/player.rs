@@ -1,38 +1,82 @@
 
 use cgmath::Vector2;
 
-use crate::graphic::InstanceData;
+use crate::graphic::{Layer, SpriteBatch};
 
-use super::{Item, Platform};
+use super::{animation::{Animation, Clip}, difficulty::Physics, item::Effects, playfield::WIDTH, Enemy, Item, Platform};
 
 #[derive(Debug)]
 pub struct Player {
     pub pos: Vector2<f32>,
+    pub prev_pos: Vector2<f32>,
     pub size: Vector2<f32>,
     pub velocity: Vector2<f32>,
-    pub movement: i8,
+    pub movement: f32,
+    pub effects: Effects,
+    pub physics: Physics,
+    // Am Rand auf der anderen Seite wieder hereinkommen, statt anzustoßen
+    pub wrap: bool,
+    pub animation: Animation,
 }
 
 impl Player {
-    
-    pub const fn create(pos: Vector2<f32>) -> Self {
-        Self { pos, size: Vector2 { x: 20.0, y: 40.0 }, velocity: Vector2 { x: 0.0, y: 460.0 }, movement: 0 }
+    pub const SIZE: Vector2<f32> = Vector2 { x: 20.0, y: 40.0 };
+    const STOMP_TOLERANCE: f32 = 8.0;
+    // Langsamer als das gilt als Scheitelpunkt des Sprungs
+    const APEX_SPEED: f32 = 150.0;
+
+    pub const fn create(pos: Vector2<f32>, physics: Physics, wrap: bool) -> Self {
+        Self { pos, prev_pos: pos, size: Self::SIZE, velocity: Vector2 { x: 0.0, y: physics.jump_velocity }, movement: 0.0, effects: Effects { jetpack: 0.0, shield: 0.0, multiplier: 0.0 }, physics, wrap, animation: Animation::new(&Clip::PLAYER_IDLE) }
     }
 
-    pub fn get_instance(&self) -> InstanceData {
-        let mut pos = self.pos;
+    pub fn draw(&self, alpha: f32, batch: &mut SpriteBatch) {
+        let mut prev_pos = self.prev_pos;
+        // Beim Sprung über den Rand nicht quer über den Bildschirm interpolieren
+        if self.wrap && (self.pos.x - prev_pos.x).abs() > WIDTH * 0.5 {
+            prev_pos.x += WIDTH * (self.pos.x - prev_pos.x).signum();
+        }
+        let mut pos = prev_pos + (self.pos - prev_pos) * alpha;
         pos.y *= -1.0;
 
-        //((high as u32) << 16) | (low as u32);
-        let uv_start = 32;
-        let uv_end = (256 << 16) | 128; 
-        InstanceData::new(pos, self.size, 0, uv_start, uv_end )
+        let sprite = self.animation.sprite();
+        batch.draw(Layer::Player, pos, self.size, sprite);
+
+        // Steht der Spieler über dem Rand, ist der Rest auf der anderen Seite zu sehen
+        if self.wrap {
+            if pos.x + self.size.x > WIDTH {
+                batch.draw(Layer::Player, Vector2 { x: pos.x - WIDTH, y: pos.y }, self.size, sprite);
+            } else if pos.x < 0.0 {
+                batch.draw(Layer::Player, Vector2 { x: pos.x + WIDTH, y: pos.y }, self.size, sprite);
+            }
+        }
     }
 
     pub fn update(&mut self, delta_time: f32) {
-        self.velocity.y -= 600.0 * delta_time;
+        self.prev_pos = self.pos;
+        if self.effects.jetpack > 0.0 {
+            self.velocity.y = self.physics.jetpack_speed;
+        } else {
+            self.velocity.y -= self.physics.gravity * delta_time;
+        }
+        self.effects.update(delta_time);
         self.pos += self.velocity * delta_time;
-        self.pos.x = (self.pos.x + self.movement as f32 * 500.0 * delta_time).clamp(0.0, 380.0);
+        let x = self.pos.x + self.movement * self.physics.move_speed * delta_time;
+        self.pos.x = if self.wrap { x.rem_euclid(WIDTH) } else { x.clamp(0.0, WIDTH - self.size.x) };
+
+        let clip = match self.velocity.y {
+            speed if speed > Self::APEX_SPEED => &Clip::PLAYER_JUMP,
+            speed if speed < -Self::APEX_SPEED => &Clip::PLAYER_FALL,
+            _ => &Clip::PLAYER_IDLE,
+        };
+        self.animation.play(clip);
+        self.animation.update(delta_time);
+    }
+
+    // Horizontale Überlappung mit [left, right]. Mit Wrap zählt auch der Teil, der links wieder hereinragt.
+    #[inline]
+    pub fn overlaps_x(&self, left: f32, right: f32) -> bool {
+        let hit = |x: f32| x + self.size.x > left && x < right;
+        hit(self.pos.x) || (self.wrap && hit(self.pos.x - WIDTH))
     }
 
     pub fn collides_with_platform(&self, prev_pos: Vector2<f32>, platform: &Platform) -> bool {
@@ -51,7 +95,7 @@ impl Player {
         let platform_top = platform.pos.y;
     
         // Überprüfung der horizontalen Überlappung (Spieler und Plattform überlappen sich in der Breite)
-        let horizontal_overlap = self.pos.x + self.size.x > platform_left && self.pos.x < platform_right;
+        let horizontal_overlap = self.overlaps_x(platform_left, platform_right);
     
         // Überprüfen, ob der Spieler die Plattform im vertikalen Raum durchquert hat
         let vertical_pass_through = prev_player_bottom + 1.0 > platform_top && player_bottom <= platform_top + 1.0;
@@ -68,15 +112,13 @@ impl Player {
             return false;
         }
 
-        let player_right = self.pos.x + self.size.x;
-        let player_left = self.pos.x;
         let player_bottom = self.pos.y - self.size.y;
         let platform_right = item.pos.x + item.size.x;
         let platform_left = item.pos.x;
         let platform_top = item.pos.y;
 
         // Überprüfung der horizontalen Überlappung (Spieler und Plattform überlappen sich in der Breite)
-        let horizontal_overlap = player_right > platform_left && player_left < platform_right;
+        let horizontal_overlap = self.overlaps_x(platform_left, platform_right);
 
         // Überprüfung, ob der Spieler von oben auf die Plattform trifft
         let vertical_overlap = player_bottom >= platform_top && player_bottom <= platform_top + 5.0; // "Toleranzbereich" der Kollision
@@ -85,8 +127,22 @@ impl Player {
         horizontal_overlap && vertical_overlap
     }
 
+    pub fn overlaps(&self, pos: Vector2<f32>, size: Vector2<f32>) -> bool {
+        self.overlaps_x(pos.x, pos.x + size.x) && self.pos.y > pos.y - size.y && self.pos.y - self.size.y < pos.y
+    }
+
+    // Trifft der Spieler fallend von oben auf den Gegner? Seitliche oder untere Berührung zählt nicht.
+    pub fn stomps(&self, prev_pos: Vector2<f32>, enemy: &Enemy) -> bool {
+        if self.velocity.y > 0.0 {
+            return false;
+        }
+
+        let prev_player_bottom = prev_pos.y - self.size.y;
+        prev_player_bottom + Self::STOMP_TOLERANCE >= enemy.pos.y
+    }
+
     pub fn jump(&mut self) {
-        self.velocity.y = 460.0;
+        self.velocity.y = self.physics.jump_velocity;
     }
 
     pub fn jump_with_strenght(&mut self, strenght: f32) {
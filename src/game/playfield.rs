@@ -0,0 +1,40 @@
+// Logische Größe der Spielwelt. Alles, was Spielregeln betrifft, rechnet in diesen Einheiten,
+// der Renderer skaliert sie ins Fenster und füllt den Rest mit Balken.
+pub const WIDTH: f32 = 400.0;
+pub const HEIGHT: f32 = 600.0;
+
+// Bereich des Fensters in Pixeln, in dem die Spielwelt gezeichnet wird
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Viewport {
+    // Größtmögliche Fläche mit dem Seitenverhältnis der Spielwelt, mittig im Fenster.
+    // Hohe Fenster bekommen Balken oben und unten, breite Fenster links und rechts.
+    pub fn fit(window_width: u32, window_height: u32) -> Self {
+        let (window_width, window_height) = (window_width as f32, window_height as f32);
+        let scale = (window_width / WIDTH).min(window_height / HEIGHT);
+        let (width, height) = (WIDTH * scale, HEIGHT * scale);
+        Self { x: ((window_width - width) * 0.5).floor(), y: ((window_height - height) * 0.5).floor(), width, height }
+    }
+}
+
+#[test]
+fn viewport_keeps_aspect_ratio() {
+    let exact = Viewport::fit(400, 600);
+    assert_eq!(exact, Viewport { x: 0.0, y: 0.0, width: 400.0, height: 600.0 });
+
+    let tall = Viewport::fit(1080, 2400);
+    assert_eq!((tall.x, tall.width), (0.0, 1080.0));
+    assert_eq!(tall.height, 1620.0);
+    assert_eq!(tall.y, 390.0);
+
+    let wide = Viewport::fit(1920, 1080);
+    assert_eq!((wide.y, wide.height), (0.0, 1080.0));
+    assert_eq!(wide.width, 720.0);
+    assert_eq!(wide.x, 600.0);
+}
@@ -0,0 +1,107 @@
+use super::{difficulty::Physics, playfield::WIDTH, Platform, Player};
+
+// Anteil der maximalen Sprunghöhe, der als sicher erreichbar gilt
+const HEIGHT_MARGIN: f32 = 0.9;
+
+// Höhe, um die die Füße des Spielers bei einem normalen Sprung maximal steigen
+#[inline]
+pub fn max_jump_height(physics: &Physics) -> f32 {
+    physics.jump_velocity * physics.jump_velocity / (2.0 * physics.gravity)
+}
+
+#[inline]
+pub fn max_safe_gap(physics: &Physics) -> f32 {
+    max_jump_height(physics) * HEIGHT_MARGIN
+}
+
+// Zeit nach dem Absprung, zu der die Füße fallend die Höhe `dy` über der Absprunghöhe durchqueren
+pub fn landing_time(dy: f32, physics: &Physics) -> Option<f32> {
+    let v = physics.jump_velocity;
+    let discriminant = v * v - 2.0 * physics.gravity * dy;
+    if discriminant < 0.0 {
+        return None;
+    }
+    Some((v + discriminant.sqrt()) / physics.gravity)
+}
+
+// Kann der Spieler von `from` abspringen und auf `to` landen? Horizontale Beweger zählen mit ihrem gesamten Bereich.
+pub fn is_reachable(from: &Platform, to: &Platform, player: &Player) -> bool {
+    if !from.kind.is_solid() || !to.kind.is_solid() {
+        return false;
+    }
+
+    // Vertikale Beweger zählen ungünstig: Absprung von ganz unten, Ziel ganz oben
+    let dy = to.vertical_span().1 - from.vertical_span().0;
+    if dy > max_safe_gap(&player.physics) {
+        return false;
+    }
+
+    let Some(time) = landing_time(dy, &player.physics) else { return false };
+
+    // Mögliche linke Kante des Spielers auf der Absprung- bzw. Zielplattform
+    let (from_left, from_right) = from.span();
+    let (to_left, to_right) = to.span();
+    let start = (from_left - player.size.x, from_right);
+    let target = (to_left - player.size.x, to_right);
+
+    let mut gap = (target.0 - start.1).max(start.0 - target.1).max(0.0);
+    // Mit Wrap geht es auch andersherum über den Rand
+    if player.wrap {
+        gap = gap.min(WIDTH - (start.1.max(target.1) - start.0.min(target.0))).max(0.0);
+    }
+    gap <= player.physics.move_speed * time
+}
+
+// Indizes aller Plattformen, die von der untersten Plattform aus nicht erreicht werden können. Zerbrechliche Plattformen werden nicht geprüft.
+pub fn find_unreachable(platforms: &[Platform], player: &Player) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..platforms.len()).filter(|&i| platforms[i].kind.is_solid()).collect();
+    order.sort_by(|&a, &b| platforms[a].pos.y.total_cmp(&platforms[b].pos.y));
+
+    let mut reachable = vec![false; platforms.len()];
+    let mut unreachable = Vec::new();
+
+    for (i, &index) in order.iter().enumerate() {
+        let platform = &platforms[index];
+        let is_start = i == 0;
+        let from_below = order[..i].iter().rev()
+            .take_while(|&&below| platform.pos.y - platforms[below].pos.y <= max_safe_gap(&player.physics))
+            .any(|&below| reachable[below] && is_reachable(&platforms[below], platform, player));
+
+        if is_start || from_below {
+            reachable[index] = true;
+        } else {
+            unreachable.push(index);
+        }
+    }
+
+    unreachable
+}
+
+#[test]
+fn gap_above_jump_height_is_unreachable() {
+    use cgmath::Vector2;
+    use super::platform::PlatformKind;
+
+    let mut player = Player::create(Vector2 { x: 0.0, y: 0.0 }, Physics::default(), false);
+    let size = Vector2 { x: 60.0, y: 12.0 };
+    let low = Platform::new(Vector2 { x: 100.0, y: 50.0 }, size, PlatformKind::Static, None);
+    let near = Platform::new(Vector2 { x: 300.0, y: 150.0 }, size, PlatformKind::Static, None);
+    let far = Platform::new(Vector2 { x: 100.0, y: 150.0 + max_jump_height(&player.physics) + 1.0 }, size, PlatformKind::Static, None);
+
+    assert!(is_reachable(&low, &near, &player));
+    assert!(!is_reachable(&low, &far, &player));
+
+    let breakable = Platform::new(Vector2 { x: 100.0, y: 100.0 }, size, PlatformKind::Breakable, None);
+    assert!(!is_reachable(&low, &breakable, &player));
+    assert!(!is_reachable(&breakable, &near, &player));
+
+    assert_eq!(find_unreachable(&[low, breakable, near, far], &player), vec![3]);
+
+    // Über den Rand ist der Weg von ganz links nach ganz rechts kurz
+    player.physics.move_speed = 100.0;
+    let left = Platform::new(Vector2 { x: 0.0, y: 50.0 }, size, PlatformKind::Static, None);
+    let right = Platform::new(Vector2 { x: WIDTH - size.x, y: 50.0 + max_safe_gap(&player.physics) }, size, PlatformKind::Static, None);
+    assert!(!is_reachable(&left, &right, &player));
+    player.wrap = true;
+    assert!(is_reachable(&left, &right, &player));
+}
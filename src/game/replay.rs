@@ -0,0 +1,224 @@
+use std::{fs, io::{self, ErrorKind}, path::Path};
+
+use super::difficulty::DEFAULT_DIFFICULTY;
+
+const MAGIC: &[u8; 4] = b"VJRP";
+// Version 2: Schwierigkeit im Header und als Event, Version 3: analoge Steuerung, Version 4: Wrap im Header und als Event
+const VERSION: u8 = 4;
+
+const KIND_MOVE_LEFT: u8 = 0;
+const KIND_MOVE_RIGHT: u8 = 1;
+// Früher direkte Verschiebung durch Touch, wird beim Lesen übersprungen
+const KIND_TOUCH: u8 = 2;
+const KIND_RESTART: u8 = 3;
+const KIND_DIFFICULTY: u8 = 4;
+const KIND_STEER: u8 = 5;
+const KIND_WRAP: u8 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Input {
+    MoveLeft(bool),
+    MoveRight(bool),
+    Restart(u64),
+    // Index des Presets, gilt ab dem nächsten Neustart
+    Difficulty(u8),
+    // Analoge Steuerung von -1 bis 1, z.B. vom Gamepad-Stick
+    Steer(f32),
+    Wrap(bool),
+}
+
+#[derive(Debug)]
+pub struct Recorder {
+    pub seed: u64,
+    pub difficulty: u8,
+    pub wrap: bool,
+    pub events: Vec<(u64, Input)>,
+}
+
+impl Recorder {
+    pub const fn new(seed: u64, difficulty: u8, wrap: bool) -> Self {
+        Self { seed, difficulty, wrap, events: Vec::new() }
+    }
+
+    #[inline]
+    pub fn record(&mut self, tick: u64, input: Input) {
+        self.events.push((tick, input));
+    }
+
+    // Format: Magic, Version, Seed, Schwierigkeit, Wrap, Anzahl, danach je Event Tick-Abstand (LEB128), Typ und Nutzdaten
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(19 + self.events.len() * 3);
+        buf.extend_from_slice(MAGIC);
+        buf.push(VERSION);
+        buf.extend_from_slice(&self.seed.to_le_bytes());
+        buf.push(self.difficulty);
+        buf.push(self.wrap as u8);
+        buf.extend_from_slice(&(self.events.len() as u32).to_le_bytes());
+
+        let mut last_tick = 0;
+        for (tick, input) in &self.events {
+            write_varint(&mut buf, tick - last_tick);
+            last_tick = *tick;
+
+            match *input {
+                Input::MoveLeft(pressed) => buf.extend_from_slice(&[KIND_MOVE_LEFT, pressed as u8]),
+                Input::MoveRight(pressed) => buf.extend_from_slice(&[KIND_MOVE_RIGHT, pressed as u8]),
+                Input::Restart(seed) => {
+                    buf.push(KIND_RESTART);
+                    buf.extend_from_slice(&seed.to_le_bytes());
+                },
+                Input::Difficulty(index) => buf.extend_from_slice(&[KIND_DIFFICULTY, index]),
+                Input::Steer(value) => {
+                    buf.push(KIND_STEER);
+                    buf.extend_from_slice(&value.to_le_bytes());
+                },
+                Input::Wrap(wrap) => buf.extend_from_slice(&[KIND_WRAP, wrap as u8]),
+            }
+        }
+
+        buf
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.encode())
+    }
+}
+
+#[derive(Debug)]
+pub struct Replay {
+    pub seed: u64,
+    pub difficulty: u8,
+    pub wrap: bool,
+    pub events: Vec<(u64, Input)>,
+    cursor: usize,
+}
+
+impl Replay {
+    pub fn decode(data: &[u8]) -> io::Result<Self> {
+        let mut reader = Reader { data, pos: 0 };
+
+        if reader.take(4)? != MAGIC {
+            return Err(io::Error::new(ErrorKind::InvalidData, "not a replay file"));
+        }
+        let version = reader.take(1)?[0];
+        if !(1..=VERSION).contains(&version) {
+            return Err(io::Error::new(ErrorKind::InvalidData, format!("unsupported replay version {version}")));
+        }
+
+        let seed = u64::from_le_bytes(reader.take(8)?.try_into().unwrap());
+        let difficulty = if version == 1 { DEFAULT_DIFFICULTY } else { reader.take(1)?[0] };
+        let wrap = version >= 4 && reader.take(1)?[0] != 0;
+        let count = u32::from_le_bytes(reader.take(4)?.try_into().unwrap()) as usize;
+
+        // Jedes Event braucht mindestens zwei Bytes, eine kaputte Anzahl darf keinen riesigen Puffer anlegen
+        let mut events = Vec::with_capacity(count.min(reader.data.len().saturating_sub(reader.pos) / 2));
+        let mut tick = 0;
+        for _ in 0..count {
+            tick += reader.varint()?;
+
+            let input = match reader.take(1)?[0] {
+                KIND_MOVE_LEFT => Input::MoveLeft(reader.take(1)?[0] != 0),
+                KIND_MOVE_RIGHT => Input::MoveRight(reader.take(1)?[0] != 0),
+                KIND_TOUCH => {
+                    reader.take(4)?;
+                    continue;
+                },
+                KIND_RESTART => Input::Restart(u64::from_le_bytes(reader.take(8)?.try_into().unwrap())),
+                KIND_DIFFICULTY => Input::Difficulty(reader.take(1)?[0]),
+                KIND_STEER => Input::Steer(f32::from_le_bytes(reader.take(4)?.try_into().unwrap())),
+                KIND_WRAP => Input::Wrap(reader.take(1)?[0] != 0),
+                kind => return Err(io::Error::new(ErrorKind::InvalidData, format!("unknown input kind {kind}"))),
+            };
+            events.push((tick, input));
+        }
+
+        Ok(Self { seed, difficulty, wrap, events, cursor: 0 })
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::decode(&fs::read(path)?)
+    }
+
+    // Liefert das nächste Event, das vor dem Simulieren von `tick` angewendet werden muss
+    #[inline]
+    pub fn next(&mut self, tick: u64) -> Option<Input> {
+        let (event_tick, input) = *self.events.get(self.cursor)?;
+        if event_tick > tick {
+            return None;
+        }
+        self.cursor += 1;
+        Some(input)
+    }
+
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.cursor >= self.events.len()
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        let slice = self.data.get(self.pos..self.pos + len).ok_or(io::Error::from(ErrorKind::UnexpectedEof))?;
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn varint(&mut self) -> io::Result<u64> {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+            if shift >= 64 {
+                return Err(io::Error::new(ErrorKind::InvalidData, "varint too long"));
+            }
+        }
+    }
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8 & 0x7F) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+#[test]
+fn replay_roundtrip() {
+    let mut recorder = Recorder::new(0xDEAD_BEEF, 2, true);
+    recorder.record(0, Input::MoveLeft(true));
+    recorder.record(3, Input::MoveLeft(false));
+    recorder.record(500, Input::MoveRight(true));
+    recorder.record(501, Input::Steer(0.375));
+    recorder.record(502, Input::Wrap(false));
+    recorder.record(89_999, Input::Difficulty(0));
+    recorder.record(90_000, Input::Restart(7));
+
+    let replay = Replay::decode(&recorder.encode()).unwrap();
+    assert_eq!(replay.seed, recorder.seed);
+    assert_eq!(replay.difficulty, recorder.difficulty);
+    assert_eq!(replay.wrap, recorder.wrap);
+    assert_eq!(replay.events, recorder.events);
+}
+
+#[test]
+fn replay_with_bogus_count_is_rejected() {
+    let mut data = Recorder::new(1, 2, false).encode();
+    let len = data.len();
+    data[len - 4..].copy_from_slice(&u32::MAX.to_le_bytes());
+
+    assert!(Replay::decode(&data).is_err());
+}
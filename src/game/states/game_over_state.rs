@@ -0,0 +1,26 @@
+use std::{cell::RefCell, rc::Rc};
+
+use iron_oxide::{graphics::formats::RGBA, ui::{Align, Style, Text, UIUnit::{self, Pixel, Zero}, UiElement, UiSize, UiState}};
+
+use super::{handle::{Handle, UiBuilder}, widgets::{button, label, text_style}};
+
+#[derive(Debug, Clone)]
+pub struct GameOverMenu {
+    pub ui: Rc<RefCell<UiState>>,
+    pub score: Handle,
+    pub restart: Handle,
+    pub title: Handle,
+}
+
+pub fn build_game_over() -> GameOverMenu {
+    let style = Style::new(Align::Top, Zero, UIUnit::Relative(0.1), UiSize::Size(UIUnit::Relative(0.8)), UiSize::Size(UIUnit::RelativeWidth(0.2)), RGBA::new(0, 0, 0, 120), RGBA::GREEN, 2.0, Pixel(10.0));
+
+    let mut builder = UiBuilder::default();
+    builder.add(UiElement::new(style, vec![Text::new(text_style(RGBA::RED), 0, "You dead", 3)]));
+    let score = builder.add(label("Score: 0", 0));
+    let restart = builder.add(button("Restart", 1));
+    let quit = builder.add(button("Quit to title", 2));
+
+    let ui = builder.build();
+    GameOverMenu { score: Handle::new(&ui, score), restart: Handle::new(&ui, restart), title: Handle::new(&ui, quit), ui }
+}
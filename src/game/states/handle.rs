@@ -0,0 +1,95 @@
+use std::{cell::RefCell, mem, rc::Rc};
+
+use iron_oxide::ui::{ErasedFnPointer, UiElement, UiState, UiType};
+
+// Sammelt die Elemente eines UI-Baums und vergibt beim Hinzufügen den Index, aus dem später der Handle entsteht
+#[derive(Default)]
+pub struct UiBuilder {
+    elements: Vec<UiElement>,
+}
+
+impl UiBuilder {
+    pub fn add(&mut self, element: UiElement) -> usize {
+        self.elements.push(element);
+        self.elements.len() - 1
+    }
+
+    pub fn build(self) -> Rc<RefCell<UiState>> {
+        Rc::new(RefCell::new(UiState::create(self.elements, Vec::with_capacity(0), true)))
+    }
+}
+
+// Benanntes Element eines UI-Baums. Der Pfad wird beim Aufbau vergeben, Spielcode greift nur über den Handle zu.
+#[derive(Debug, Clone)]
+pub struct Handle {
+    ui: Rc<RefCell<UiState>>,
+    path: Vec<usize>,
+}
+
+impl Handle {
+    pub fn new(ui: &Rc<RefCell<UiState>>, index: usize) -> Self {
+        Self { ui: ui.clone(), path: vec![index] }
+    }
+
+    // Setzt den Text des Elements oder, bei Blöcken und Buttons, den seines ersten Text-Kindes
+    pub fn set_text(&self, text: &str) {
+        self.with_element(|element| {
+            if matches!(element.inherit, UiType::Text(_)) {
+                return set_element_text(element, text);
+            }
+            match element.childs.iter_mut().find(|child| matches!(child.inherit, UiType::Text(_))) {
+                Some(child) => set_element_text(child, text),
+                None => log::error!("ui element {:?} has no text", self.path),
+            }
+        });
+    }
+
+    pub fn set_visible(&self, visible: bool) {
+        self.with_element(|element| {
+            if element.visible != visible {
+                element.visible = visible;
+                element.dirty = true;
+            }
+        });
+    }
+
+    // Der Button merkt sich `target` als rohen Zeiger. Der Aufrufer muss sicherstellen, dass `target` weder verschoben
+    // noch freigegeben wird, solange der UI-Baum des Handles Eingaben verarbeitet.
+    pub unsafe fn on_press<T>(&self, target: &mut T, callback: fn(&mut T, &mut UiState, &mut UiElement)) {
+        self.with_element(|element| match &mut element.inherit {
+            UiType::Button(button) => button.on_press = ErasedFnPointer::from_associated_ui(target, callback),
+            _ => log::error!("ui element {:?} is not a button", self.path),
+        });
+    }
+
+    fn with_element(&self, f: impl FnOnce(&mut UiElement)) {
+        let mut ui = self.ui.borrow_mut();
+        // Der Pfad stammt aus dem UiBuilder desselben Baums und ist damit gültig
+        let element = unsafe { ui.get_element_mut(self.path.clone()) }.expect("ui handle points outside of its tree");
+        f(element);
+        ui.dirty = true;
+    }
+}
+
+// Text::set_text braucht neben dem Text auch das Element, in dem er steckt. Der Text wird dafür kurz herausgenommen, damit keine zwei &mut auf dasselbe zeigen.
+fn set_element_text(element: &mut UiElement, text: &str) {
+    if let UiType::Text(mut inner) = mem::replace(&mut element.inherit, UiType::Block) {
+        inner.set_text(element, text);
+        element.inherit = UiType::Text(inner);
+    }
+    element.dirty = true;
+}
+
+#[test]
+fn handles_follow_build_order() {
+    use super::widgets::{button, label};
+
+    let mut builder = UiBuilder::default();
+    let first = builder.add(label("first", 0));
+    let second = builder.add(button("second", 1));
+    let ui = builder.build();
+
+    Handle::new(&ui, second).set_visible(false);
+    assert!(ui.borrow().get_element(vec![first]).unwrap().visible);
+    assert!(!ui.borrow().get_element(vec![second]).unwrap().visible);
+}
@@ -0,0 +1,40 @@
+use std::{cell::RefCell, rc::Rc};
+
+use iron_oxide::{graphics::formats::RGBA, ui::{Align, Button, Style, Text, UIUnit::{self, Pixel, Zero}, UiElement, UiSize, UiState}};
+
+use crate::game::highscore::{format_date, HighScores};
+
+use super::{handle::{Handle, UiBuilder}, widgets::text_style};
+
+#[derive(Debug, Clone)]
+pub struct HighscoreTable {
+    pub ui: Rc<RefCell<UiState>>,
+    pub back: Handle,
+}
+
+pub fn build_highscores(scores: &HighScores) -> HighscoreTable {
+    let title_style = Style::new(Align::Top, Zero, Pixel(10.0), UiSize::Size(UIUnit::Relative(0.6)), UiSize::Size(UIUnit::RelativeWidth(0.1)), RGBA::new(0, 0, 0, 120), RGBA::GREEN, 2.0, Pixel(10.0));
+
+    let back_style = |border| Style::new(Align::Top, Zero, UIUnit::Relative(0.88), UiSize::Size(UIUnit::Relative(0.4)), UiSize::Size(UIUnit::RelativeWidth(0.08)), RGBA::BLACK, border, 2.0, Pixel(10.0));
+
+    let mut builder = UiBuilder::default();
+    builder.add(UiElement::new(title_style, vec![Text::new(text_style(RGBA::GREEN), 0, "Highscores", 3)]));
+    let back = builder.add(Button::new(back_style(RGBA::GREEN), back_style(RGBA::BLUE), back_style(RGBA::PURPLE), vec![Text::new(text_style(RGBA::GREEN), 0, "Back", 3)]));
+
+    if scores.entries.is_empty() {
+        builder.add(UiElement::new(row_style(0), vec![Text::new(text_style(RGBA::GREEN), 0, "No runs yet", 3)]));
+    }
+
+    for (i, entry) in scores.entries.iter().enumerate() {
+        let duration = entry.duration as u32;
+        let line = format!("{}. {}  {}  {}:{:02}  #{}", i + 1, entry.score, format_date(entry.date), duration / 60, duration % 60, entry.seed);
+        builder.add(UiElement::new(row_style(i), vec![Text::new(text_style(RGBA::GREEN), 0, &line, 3)]));
+    }
+
+    let ui = builder.build();
+    HighscoreTable { back: Handle::new(&ui, back), ui }
+}
+
+fn row_style(row: usize) -> Style {
+    Style::new(Align::Top, Zero, UIUnit::Relative(0.15 + row as f32 * 0.07), UiSize::Size(UIUnit::Relative(0.9)), UiSize::Size(UIUnit::RelativeWidth(0.06)), RGBA::new(0, 0, 0, 120), RGBA::GREEN, 0.0, Pixel(5.0))
+}
@@ -1,9 +1,20 @@
-use iron_oxide::{graphics::formats::RGBA, ui::{Align::{self}, Button, Inline, Overflow, Padding, Position, Style, Text, UIUnit::{self, Pixel, Zero}, UiElement, UiSize::{self}, UiState}};
+use std::{cell::RefCell, rc::Rc};
 
+use iron_oxide::{graphics::formats::RGBA, ui::{Align::{self}, Inline, Overflow, Padding, Position, Style, Text, UIUnit::{self, Pixel, Zero}, UiElement, UiSize::{self}, UiState}};
 
-pub fn build_main() -> UiState {
+use super::handle::{Handle, UiBuilder};
+
+// HUD während des Spiels
+#[derive(Debug, Clone)]
+pub struct Hud {
+    pub ui: Rc<RefCell<UiState>>,
+    pub score: Handle,
+    pub seed: Handle,
+    pub effects: Handle,
+}
+
+pub fn build_main() -> Hud {
     let style = Style::new(Align::Top, Zero, Pixel(10.0), UiSize::Size(UIUnit::Relative(0.3)), UiSize::Size(UIUnit::RelativeWidth(0.1)), RGBA::new(0, 0, 0, 120), RGBA::GREEN, 2.0, Pixel(10.0));
-    let style2 = Style::new(Align::Center, Zero, Zero, UiSize::Size(UIUnit::Relative(0.8)), UiSize::Size(UIUnit::RelativeWidth(0.2)), RGBA::new(0, 0, 0, 120), RGBA::GREEN, 2.0, Pixel(10.0));
 
     let text_style = Style {
         position: Position::Inline(Inline { margin: [UIUnit::Pixel(0.0); 4], overflow: Overflow::clip() }),
@@ -16,29 +27,39 @@ pub fn build_main() -> UiState {
         ..Default::default()
     };
 
-    let text_style2 = Style {
+    let seed_style = Style::new(Align::Top, Zero, UIUnit::Relative(0.92), UiSize::Size(UIUnit::Relative(0.5)), UiSize::Size(UIUnit::RelativeWidth(0.06)), RGBA::new(0, 0, 0, 120), RGBA::GREEN, 0.0, Pixel(5.0));
+
+    let seed_text_style = Style {
         position: Position::Inline(Inline { margin: [UIUnit::Pixel(0.0); 4], overflow: Overflow::clip() }),
         width: UiSize::Fill,
-        height: UiSize::Size(UIUnit::RelativeHeight(0.6)),
-        color: RGBA::RED,
+        height: UiSize::Size(UIUnit::Relative(0.6)),
+        color: RGBA::GREEN,
         border: [0.0; 4],
-        //corner: [UIUnit::RelativeHeight(0.5); 4],
         padding: Padding::new(0.0),
         ..Default::default()
     };
 
+    let effects_style = Style::new(Align::Top, Zero, UIUnit::Relative(0.12), UiSize::Size(UIUnit::Relative(0.6)), UiSize::Size(UIUnit::RelativeWidth(0.06)), RGBA::new(0, 0, 0, 120), RGBA::GREEN, 0.0, Pixel(5.0));
+
+    let effects_text_style = Style {
+        position: Position::Inline(Inline { margin: [UIUnit::Pixel(0.0); 4], overflow: Overflow::clip() }),
+        width: UiSize::Fill,
+        height: UiSize::Size(UIUnit::Relative(0.6)),
+        color: RGBA::GREEN,
+        border: [0.0; 4],
+        padding: Padding::new(0.0),
+        ..Default::default()
+    };
 
-    let style_normal = Style::new(Align::Top, Zero, UIUnit::Relative(0.55), UiSize::Size(UIUnit::Relative(0.3)), UiSize::Size(UIUnit::RelativeWidth(0.1)), RGBA::BLACK, RGBA::GREEN, 2.0, Pixel(10.0));
-    let style_hover = Style::new(Align::Top, Zero, UIUnit::Relative(0.55), UiSize::Size(UIUnit::Relative(0.3)), UiSize::Size(UIUnit::RelativeWidth(0.1)), RGBA::BLACK, RGBA::BLUE, 2.0, Pixel(10.0));
-    let style_press = Style::new(Align::Top, Zero, UIUnit::Relative(0.55), UiSize::Size(UIUnit::Relative(0.3)), UiSize::Size(UIUnit::RelativeWidth(0.1)), RGBA::BLACK, RGBA::PURPLE, 2.0, Pixel(10.0));
+    let mut builder = UiBuilder::default();
+    let score = builder.add(UiElement::new(style, vec![Text::new(text_style, 0, "0", 3)]));
 
-    let score = UiElement::new(style, vec![Text::new(text_style, 0, "0", 3)]);
-    let mut dead_message = UiElement::new(style2, vec![Text::new(text_style2, 0,"You dead", 3)]);
-    dead_message.visible = false;
+    let seed = builder.add(UiElement::new(seed_style, vec![Text::new(seed_text_style, 0, "Seed: 0", 3)]));
 
-    let mut respawn_button = Button::new(style_normal, style_hover, style_press, Vec::with_capacity(0));
-    respawn_button.visible = false;
+    let mut effects_element = UiElement::new(effects_style, vec![Text::new(effects_text_style, 0, "", 3)]);
+    effects_element.visible = false;
+    let effects = builder.add(effects_element);
 
-    let state = UiState::create(vec![score, dead_message, respawn_button], Vec::with_capacity(0), true);
-    state
+    let ui = builder.build();
+    Hud { score: Handle::new(&ui, score), seed: Handle::new(&ui, seed), effects: Handle::new(&ui, effects), ui }
 }
\ No newline at end of file
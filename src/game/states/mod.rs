@@ -1,2 +1,211 @@
+use std::{cell::RefCell, rc::Rc};
+
+use iron_oxide::ui::{UiElement, UiState};
+
+use super::controls::Action;
+
 mod main_state;
-pub use main_state::build_main;
\ No newline at end of file
+mod highscore_state;
+mod title_state;
+mod pause_state;
+mod game_over_state;
+mod settings_state;
+mod widgets;
+mod handle;
+
+pub use main_state::{build_main, Hud};
+pub use highscore_state::{build_highscores, HighscoreTable};
+pub use title_state::{build_title, TitleMenu};
+pub use pause_state::{build_pause, PauseMenu};
+pub use game_over_state::{build_game_over, GameOverMenu};
+pub use settings_state::{build_settings, SettingsMenu};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameState {
+    Title,
+    Playing,
+    Paused,
+    GameOver,
+    Settings,
+    Highscores,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuAction {
+    Play,
+    Pause,
+    Resume,
+    Settings,
+    Highscores,
+    Back,
+    Title,
+    NextDifficulty,
+    ToggleWrap,
+    NextCamera,
+    // Die nächste gedrückte Taste wird der Aktion zugewiesen
+    Rebind(Action),
+    ToggleTilt,
+    Screenshot,
+}
+
+// Jeder Zustand besitzt seinen eigenen UI-Baum, angezeigt wird immer der oberste Zustand.
+// Button-Callbacks merken sich nur die Aktion, die App führt sie nach dem Event aus.
+#[derive(Debug)]
+pub struct StateStack {
+    stack: Vec<GameState>,
+    pub pending: Option<MenuAction>,
+    pub hud: Hud,
+    pub title: TitleMenu,
+    pub paused: PauseMenu,
+    pub game_over: GameOverMenu,
+    pub settings: SettingsMenu,
+    pub highscores: HighscoreTable,
+}
+
+impl StateStack {
+    pub fn create(hud: Hud, highscores: HighscoreTable) -> Self {
+        Self {
+            stack: vec![GameState::Title],
+            pending: None,
+            hud,
+            title: build_title(),
+            paused: build_pause(),
+            game_over: build_game_over(),
+            settings: build_settings(),
+            highscores,
+        }
+    }
+
+    #[inline]
+    pub fn top(&self) -> GameState {
+        *self.stack.last().unwrap()
+    }
+
+    pub fn ui(&self, state: GameState) -> &Rc<RefCell<UiState>> {
+        match state {
+            GameState::Title => &self.title.ui,
+            GameState::Playing => &self.hud.ui,
+            GameState::Paused => &self.paused.ui,
+            GameState::GameOver => &self.game_over.ui,
+            GameState::Settings => &self.settings.ui,
+            GameState::Highscores => &self.highscores.ui,
+        }
+    }
+
+    pub fn push(&mut self, state: GameState) {
+        self.stack.push(state);
+    }
+
+    // Der unterste Zustand bleibt immer erhalten
+    pub fn pop(&mut self) {
+        if self.stack.len() > 1 {
+            self.stack.pop();
+        }
+    }
+
+    pub fn replace(&mut self, state: GameState) {
+        *self.stack.last_mut().unwrap() = state;
+    }
+
+    pub fn reset(&mut self, state: GameState) {
+        self.stack.clear();
+        self.stack.push(state);
+    }
+
+    pub fn on_play(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::Play);
+    }
+
+    pub fn on_resume(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::Resume);
+    }
+
+    pub fn on_settings(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::Settings);
+    }
+
+    pub fn on_highscores(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::Highscores);
+    }
+
+    pub fn on_back(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::Back);
+    }
+
+    pub fn on_title(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::Title);
+    }
+
+    pub fn on_next_difficulty(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::NextDifficulty);
+    }
+
+    pub fn on_toggle_wrap(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::ToggleWrap);
+    }
+
+    pub fn on_next_camera(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::NextCamera);
+    }
+
+    pub fn on_toggle_tilt(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::ToggleTilt);
+    }
+
+    pub fn on_screenshot(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::Screenshot);
+    }
+
+    // Button-Callbacks tragen keine Daten, daher eine Funktion je belegbarer Aktion
+    pub fn on_rebind_move_left(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::Rebind(Action::MoveLeft));
+    }
+
+    pub fn on_rebind_move_right(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::Rebind(Action::MoveRight));
+    }
+
+    pub fn on_rebind_pause(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::Rebind(Action::Pause));
+    }
+
+    pub fn on_rebind_toggle_hud(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::Rebind(Action::ToggleHud));
+    }
+
+    pub fn on_rebind_restart(&mut self, _: &mut UiState, _: &mut UiElement) {
+        self.pending = Some(MenuAction::Rebind(Action::Restart));
+    }
+
+    pub fn rebind_callback(action: Action) -> Option<fn(&mut Self, &mut UiState, &mut UiElement)> {
+        Some(match action {
+            Action::MoveLeft => Self::on_rebind_move_left,
+            Action::MoveRight => Self::on_rebind_move_right,
+            Action::Pause => Self::on_rebind_pause,
+            Action::ToggleHud => Self::on_rebind_toggle_hud,
+            Action::Restart => Self::on_rebind_restart,
+            _ => return None,
+        })
+    }
+}
+
+#[test]
+fn state_stack_transitions() {
+    use super::highscore::HighScores;
+
+    let mut states = StateStack::create(build_main(), build_highscores(&HighScores::default()));
+    assert_eq!(states.top(), GameState::Title);
+
+    states.reset(GameState::Playing);
+    states.push(GameState::Paused);
+    states.push(GameState::Settings);
+    states.pop();
+    assert_eq!(states.top(), GameState::Paused);
+    states.pop();
+    assert_eq!(states.top(), GameState::Playing);
+
+    states.pop();
+    assert_eq!(states.top(), GameState::Playing);
+    states.replace(GameState::GameOver);
+    assert!(Rc::ptr_eq(states.ui(states.top()), &states.game_over.ui));
+}
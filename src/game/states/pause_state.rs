@@ -0,0 +1,26 @@
+use std::{cell::RefCell, rc::Rc};
+
+use iron_oxide::ui::UiState;
+
+use super::{handle::{Handle, UiBuilder}, widgets::{button, title}};
+
+#[derive(Debug, Clone)]
+pub struct PauseMenu {
+    pub ui: Rc<RefCell<UiState>>,
+    pub resume: Handle,
+    pub settings: Handle,
+    pub title: Handle,
+    pub screenshot: Handle,
+}
+
+pub fn build_pause() -> PauseMenu {
+    let mut builder = UiBuilder::default();
+    builder.add(title("Paused"));
+    let resume = builder.add(button("Resume", 0));
+    let settings = builder.add(button("Settings", 1));
+    let quit = builder.add(button("Quit to title", 2));
+    let screenshot = builder.add(button("Screenshot", 3));
+
+    let ui = builder.build();
+    PauseMenu { resume: Handle::new(&ui, resume), settings: Handle::new(&ui, settings), title: Handle::new(&ui, quit), screenshot: Handle::new(&ui, screenshot), ui }
+}
@@ -0,0 +1,44 @@
+use std::{cell::RefCell, rc::Rc};
+
+use iron_oxide::ui::UiState;
+
+use crate::game::controls::Action;
+
+use super::{handle::{Handle, UiBuilder}, widgets::{compact_button, title}};
+
+// Aktionen, deren Taste im Menü geändert werden kann
+const REBINDABLE: [Action; 5] = [Action::MoveLeft, Action::MoveRight, Action::Pause, Action::ToggleHud, Action::Restart];
+
+#[derive(Debug, Clone)]
+pub struct SettingsMenu {
+    pub ui: Rc<RefCell<UiState>>,
+    pub difficulty: Handle,
+    pub wrap: Handle,
+    pub camera: Handle,
+    pub bindings: Vec<(Action, Handle)>,
+    // Nur sichtbar, wenn das Gerät einen Beschleunigungssensor hat
+    pub tilt: Handle,
+    pub back: Handle,
+}
+
+pub fn build_settings() -> SettingsMenu {
+    let mut builder = UiBuilder::default();
+    builder.add(title("Settings"));
+    let difficulty = builder.add(compact_button("Difficulty: normal", 0));
+    let wrap = builder.add(compact_button("Wrap around: off", 1));
+    let camera = builder.add(compact_button("Camera: smooth", 2));
+    let bindings: Vec<(Action, usize)> = REBINDABLE.iter().enumerate().map(|(row, &action)| (action, builder.add(compact_button(action.label(), row + 3)))).collect();
+    let tilt = builder.add(compact_button("Tilt steering: off", REBINDABLE.len() + 3));
+    let back = builder.add(compact_button("Back", REBINDABLE.len() + 4));
+
+    let ui = builder.build();
+    SettingsMenu {
+        difficulty: Handle::new(&ui, difficulty),
+        wrap: Handle::new(&ui, wrap),
+        camera: Handle::new(&ui, camera),
+        bindings: bindings.into_iter().map(|(action, index)| (action, Handle::new(&ui, index))).collect(),
+        tilt: Handle::new(&ui, tilt),
+        back: Handle::new(&ui, back),
+        ui,
+    }
+}
@@ -0,0 +1,24 @@
+use std::{cell::RefCell, rc::Rc};
+
+use iron_oxide::ui::UiState;
+
+use super::{handle::{Handle, UiBuilder}, widgets::{button, title}};
+
+#[derive(Debug, Clone)]
+pub struct TitleMenu {
+    pub ui: Rc<RefCell<UiState>>,
+    pub play: Handle,
+    pub settings: Handle,
+    pub highscores: Handle,
+}
+
+pub fn build_title() -> TitleMenu {
+    let mut builder = UiBuilder::default();
+    builder.add(title("Vudeljump"));
+    let play = builder.add(button("Play", 0));
+    let settings = builder.add(button("Settings", 1));
+    let highscores = builder.add(button("Highscores", 2));
+
+    let ui = builder.build();
+    TitleMenu { play: Handle::new(&ui, play), settings: Handle::new(&ui, settings), highscores: Handle::new(&ui, highscores), ui }
+}
@@ -0,0 +1,41 @@
+use iron_oxide::{graphics::formats::RGBA, ui::{Align, Button, Inline, Overflow, Padding, Position, Style, Text, UIUnit::{self, Pixel, Zero}, UiElement, UiSize}};
+
+// Gemeinsame Bausteine für die Menüs, `row` zählt die Zeilen von oben
+
+pub fn title(label: &str) -> UiElement {
+    let style = Style::new(Align::Top, Zero, UIUnit::Relative(0.1), UiSize::Size(UIUnit::Relative(0.7)), UiSize::Size(UIUnit::RelativeWidth(0.12)), RGBA::new(0, 0, 0, 120), RGBA::GREEN, 2.0, Pixel(10.0));
+    UiElement::new(style, vec![Text::new(text_style(RGBA::GREEN), 0, label, 3)])
+}
+
+pub fn label(text: &str, row: usize) -> UiElement {
+    UiElement::new(row_style(row, RGBA::new(0, 0, 0, 120), RGBA::GREEN), vec![Text::new(text_style(RGBA::GREEN), 0, text, 3)])
+}
+
+pub fn button(label: &str, row: usize) -> UiElement {
+    Button::new(row_style(row, RGBA::BLACK, RGBA::GREEN), row_style(row, RGBA::BLACK, RGBA::BLUE), row_style(row, RGBA::BLACK, RGBA::PURPLE), vec![Text::new(text_style(RGBA::GREEN), 0, label, 3)])
+}
+
+// Engere Zeilen für Menüs mit vielen Einträgen, etwa die Tastenbelegung
+pub fn compact_button(label: &str, row: usize) -> UiElement {
+    Button::new(compact_style(row, RGBA::BLACK, RGBA::GREEN), compact_style(row, RGBA::BLACK, RGBA::BLUE), compact_style(row, RGBA::BLACK, RGBA::PURPLE), vec![Text::new(text_style(RGBA::GREEN), 0, label, 3)])
+}
+
+fn row_style(row: usize, color: RGBA, border_color: RGBA) -> Style {
+    Style::new(Align::Top, Zero, UIUnit::Relative(0.3 + row as f32 * 0.14), UiSize::Size(UIUnit::Relative(0.6)), UiSize::Size(UIUnit::RelativeWidth(0.1)), color, border_color, 2.0, Pixel(10.0))
+}
+
+fn compact_style(row: usize, color: RGBA, border_color: RGBA) -> Style {
+    Style::new(Align::Top, Zero, UIUnit::Relative(0.25 + row as f32 * 0.07), UiSize::Size(UIUnit::Relative(0.8)), UiSize::Size(UIUnit::RelativeWidth(0.08)), color, border_color, 2.0, Pixel(6.0))
+}
+
+pub fn text_style(color: RGBA) -> Style {
+    Style {
+        position: Position::Inline(Inline { margin: [UIUnit::Pixel(0.0); 4], overflow: Overflow::clip() }),
+        width: UiSize::Fill,
+        height: UiSize::Size(UIUnit::Relative(0.6)),
+        color,
+        border: [0.0; 4],
+        padding: Padding::new(0.0),
+        ..Default::default()
+    }
+}
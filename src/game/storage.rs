@@ -0,0 +1,39 @@
+use std::{env, path::PathBuf, sync::OnceLock};
+
+const APP_DIR: &str = "vudeljump";
+
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+// Auf Android wird der interne Speicherpfad der Activity beim Start gesetzt
+#[allow(dead_code)]
+pub fn set_data_dir(path: PathBuf) {
+    let _ = DATA_DIR.set(path);
+}
+
+pub fn data_dir() -> PathBuf {
+    if let Some(path) = DATA_DIR.get() {
+        return path.clone();
+    }
+
+    desktop_data_dir().map_or(PathBuf::from("."), |dir| dir.join(APP_DIR))
+}
+
+#[inline]
+pub fn data_file(name: &str) -> PathBuf {
+    data_dir().join(name)
+}
+
+#[cfg(target_os = "windows")]
+fn desktop_data_dir() -> Option<PathBuf> {
+    env::var_os("APPDATA").map(PathBuf::from)
+}
+
+#[cfg(target_os = "macos")]
+fn desktop_data_dir() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn desktop_data_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME").map(PathBuf::from).or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+}
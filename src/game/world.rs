@@ -1,78 +1,148 @@
-use std::{cell::RefCell, rc::Rc};
-
-use cgmath::Vector2;
-use iron_oxide::ui::{Text, UiElement, UiState, UiType};
-use rand::Rng;
+use cgmath::{Matrix4, Vector2};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 pub use crate::game::Player;
-use crate::graphic::InstanceData;
-
-use super::{Item, Platform};
+use crate::graphic::SpriteBatch;
+
+use super::{background, camera::Camera, difficulty::{Difficulty, DEFAULT_DIFFICULTY}, enemy::{Enemy, EnemyKind}, highscore::ScoreEntry, item::{ItemKind, Pickup}, platform::PlatformKind, playfield::{HEIGHT, WIDTH}, reachability, replay::{Input, Recorder, Replay}, states::Hud, Item, Platform};
+
+pub const TICK_RATE: u32 = 120;
+pub const TICK_TIME: f32 = 1.0 / TICK_RATE as f32;
+// Längere Frames (Fenster verschoben, Breakpoint) werden gekappt, statt hunderte Ticks nachzuholen
+const MAX_FRAME_TIME: f32 = 0.25;
+// Bildschirmwackeln: Landungen abhängig von der Fallgeschwindigkeit, Treffer fest
+const LANDING_TRAUMA_SPEED: f32 = 2500.0;
+const MAX_LANDING_TRAUMA: f32 = 0.4;
+const STOMP_TRAUMA: f32 = 0.3;
+const HIT_TRAUMA: f32 = 0.6;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RunStats {
+    pub platforms_generated: u32,
+    pub items_collected: u32,
+    pub enemies_killed: u32,
+    pub death_height: Option<f32>,
+}
 
 #[repr(C)]
 #[derive(Debug)]
 pub struct World {
     pub platforms: Vec<Platform>,
-    //pub enemies: Vec<()>,
+    pub enemies: Vec<Enemy>,
+    pub pickups: Vec<Pickup>,
     pub player: Player,
     pub view_start: u32,
     pub view_end: u32,
-    pub current_view: f32,
+    pub camera: Camera,
     pub gen_heigt: f32,
     pub platform_density: f32,
     pub score: u32,
-    pub ui: Rc<RefCell<UiState>>,
-    dead: bool,
+    pub max_height: f32,
+    score_progress: f32,
+    effects_text: String,
+    pub stats: RunStats,
+    pub finished_run: Option<ScoreEntry>,
+    run_start_tick: u64,
+    pub hud: Hud,
+    pub seed: u64,
+    pub difficulties: Vec<Difficulty>,
+    pub difficulty: u8,
+    // Horizontales Wrap-around statt Wänden am Bildschirmrand
+    pub wrap: bool,
+    rng: StdRng,
+    pub recorder: Recorder,
+    pub replay: Option<Replay>,
+    pub tick: u64,
+    accumulator: f32,
+    alpha: f32,
+    pub dead: bool,
 }
 
 impl World {
 
-    pub fn create(ui: Rc<RefCell<UiState>>) -> Self {
+    pub fn create(hud: Hud, difficulties: Vec<Difficulty>) -> Self {
+        Self::create_with_seed(hud, rand::random(), difficulties)
+    }
+
+    pub fn create_with_seed(hud: Hud, seed: u64, difficulties: Vec<Difficulty>) -> Self {
+        let difficulty = DEFAULT_DIFFICULTY.min(difficulties.len() as u8 - 1);
         let mut platforms = Vec::with_capacity(30);
-        platforms.push(Platform::new(Vector2 { x: 170.0, y: 50.0 }, Vector2 { x: 60.0, y: 12.0 }, 0, None));
+        platforms.push(Platform::new(Vector2 { x: WIDTH * 0.5 - 30.0, y: 50.0 }, Vector2 { x: 60.0, y: 12.0 }, PlatformKind::Static, None));
 
-        Self {
+        let world = Self {
             platforms,
-            //enemies: Vec::with_capacity(10),
-            player: Player::create(Vector2 { x: 185.0, y: 0.0 }),
+            enemies: Vec::with_capacity(10),
+            pickups: Vec::new(),
+            player: Player::create(Vector2 { x: WIDTH * 0.5 - 15.0, y: 0.0 }, difficulties[difficulty as usize].physics, false),
             view_start: 0,
-            view_end: 600,
-            current_view: 0.0,
+            view_end: HEIGHT as u32,
+            camera: Camera::new(),
             gen_heigt: 75.0,
             platform_density: 0.0,
             score: 0,
-            ui,
+            max_height: 0.0,
+            score_progress: 0.0,
+            effects_text: String::new(),
+            stats: RunStats::default(),
+            finished_run: None,
+            run_start_tick: 0,
+            hud,
+            seed,
+            difficulties,
+            difficulty,
+            wrap: false,
+            rng: StdRng::seed_from_u64(seed),
+            recorder: Recorder::new(seed, difficulty, false),
+            replay: None,
+            tick: 0,
+            accumulator: 0.0,
+            alpha: 0.0,
             dead: false,
-        }
-    }
+        };
 
-    pub fn get_instances(&self) -> Vec<InstanceData> {
-        let mut vec = Vec::with_capacity(20);
+        world.hud.seed.set_text(&format!("Seed: {seed}"));
+        world
+    }
 
+    // Die Ebene legt die Reihenfolge fest, nicht die Reihenfolge der Aufrufe
+    pub fn draw(&self, batch: &mut SpriteBatch) {
+        background::draw(self.camera.view(self.alpha), batch);
         for platform in &self.platforms {
-            platform.get_instance(&mut vec);
+            platform.draw(self.alpha, batch);
         }
-        vec.push(self.player.get_instance());
-
-        vec
+        for enemy in &self.enemies {
+            enemy.draw(self.alpha, batch);
+        }
+        for pickup in &self.pickups {
+            pickup.draw(batch);
+        }
+        self.player.draw(self.alpha, batch);
     }
 
     #[inline]
     fn remove_platforms_below(&mut self) {
         self.platforms.retain(|platform| platform.pos.y > self.view_start as f32); // Behalte nur Plattformen oberhalb des Bildschirms
+        self.enemies.retain(|enemy| enemy.pos.y > self.view_start as f32);
     }
 
     fn generate_platforms(&mut self) {
-        let y_spacing = 30.0;
-        let max_x = 320.0;
-        let hardness = (self.score as f32).sqrt() / 60.0;           // Rechter Rand des Bildschirms (Beispiel: Bildschirmbreite)
+        let difficulty = &self.difficulties[self.difficulty as usize];
+        let physics = &self.player.physics;
+        let y_spacing = difficulty.row_spacing;
+        let max_x = WIDTH - 80.0;    // Plattformbreite und Abstand zum rechten Rand
+        let hardness = difficulty.hardness(self.score);
 
-        let mut rng = rand::thread_rng();
+        let rng = &mut self.rng;
+        let start_height = self.gen_heigt;
 
         // Generiere neue Plattformen, wenn der Spieler nach oben gesprungen ist
-        while self.gen_heigt < self.view_end as f32 {
+        // Die Kamera kann beim schnellen Steigen über view_end hinaus schauen
+        while self.gen_heigt < self.view_end as f32 + Camera::LOOK_AHEAD_MAX {
 
-            if rng.gen_range(0.0..1.3) <= hardness * self.platform_density {
+            // Reihen nur auslassen, solange die nächste Plattform noch erreichbar bleibt
+            let next_height = self.gen_heigt + 2.0 * y_spacing;
+            let can_skip = self.platforms.iter().rev().find(|platform| platform.kind.is_solid()).is_none_or(|last| next_height - last.vertical_span().0 <= reachability::max_safe_gap(physics));
+            if rng.gen_range(0.0..difficulty.skip_range) <= hardness * self.platform_density && can_skip {
                 self.gen_heigt += y_spacing;
                 self.platform_density -= 1.0 / 3.0;
                 continue;
@@ -83,39 +153,157 @@ impl World {
             // Zufällige X-Position im sichtbaren Bereich
             let x_position = rng.gen_range(0.0..max_x);
 
-            let mut platform;
-
-            if rng.gen_range(0.0..9.0) <= hardness.clamp(0.0, 5.0) {
-                platform = Platform {
-                    pos: Vector2 { x: x_position, y: y_position },
-                    size: Vector2 { x: 60.0, y: 12.0 },  // Beispielsgröße
-                    id: 1,
-                    direction: 1.0,
-                    item: None
-                }
+            let special_chance = hardness.min(difficulty.special_max_hardness) * 0.5;
+            let kind = if rng.gen_range(0.0..difficulty.mover_range) <= hardness.clamp(0.0, difficulty.mover_max_hardness) {
+                if rng.gen_range(0.0..1.0) < difficulty.vertical_share { PlatformKind::Vertical } else { PlatformKind::Horizontal }
             } else {
-                platform = Platform {
-                    pos: Vector2 { x: x_position, y: y_position },
-                    size: Vector2 { x: 60.0, y: 12.0 },
-                    id: 0,
-                    direction: 0.0,
-                    item: None
+                match rng.gen_range(0.0..difficulty.special_range) {
+                    roll if roll < special_chance && can_skip => PlatformKind::Breakable,
+                    roll if roll < special_chance * 2.0 => PlatformKind::Vanishing,
+                    roll if roll < special_chance * 3.0 => PlatformKind::OneShot,
+                    _ => PlatformKind::Static,
+                }
+            };
+
+            let mut platform = Platform::new(Vector2 { x: x_position, y: y_position }, Vector2 { x: 60.0, y: 12.0 }, kind, None);
+
+            // Unerreichbare Plattformen werden zu statischen Plattformen über der letzten festen Plattform
+            if let Some(last) = self.platforms.iter().rev().find(|platform| platform.kind.is_solid())
+                && kind.is_solid() && !reachability::is_reachable(last, &platform, &self.player) {
+                platform = Platform::new(Vector2 { x: last.pos.x, y: y_position }, platform.size, PlatformKind::Static, None);
+            }
+
+            if rng.gen_range(1..difficulty.item_range as u32) == 1 + hardness.min(difficulty.item_max_hardness) as u32 {
+                let kind = match rng.gen_range(0..20) {
+                    0..11 => ItemKind::Spring,
+                    11..14 => ItemKind::Jetpack,
+                    14..17 => ItemKind::Shield,
+                    _ => ItemKind::Multiplier,
                 };
+                platform.item = Some(Item { pos: Vector2 { x: platform.pos.x + rng.gen_range(0.0..40.0), y: y_position + 15.0 }, size: Vector2 { x: 20.0, y: 20.0 }, kind });
             }
 
-            if rng.gen_range(1..20) == 1 + hardness.min(3.0) as u32 {
-                platform.item = Some(Item { pos: Vector2 { x: x_position + rng.gen_range(0.0..40.0), y: y_position + 15.0 }, size: Vector2 { x: 20.0, y: 20.0 }, typ: 1 });
+            // Gegner erst ab einer gewissen Höhe, bewegliche Gegner noch später
+            if rng.gen_range(0.0..1.0) < (hardness * difficulty.enemy_rate).min(difficulty.enemy_max_chance) {
+                let kind = match rng.gen_range(0.0..hardness.max(0.1)) {
+                    roll if roll > 1.0 => EnemyKind::Hover,
+                    roll if roll > 0.5 => EnemyKind::Patrol,
+                    _ => EnemyKind::Stationary,
+                };
+                let enemy_x = (platform.pos.x + platform.size.x + rng.gen_range(20.0..200.0)) % (max_x + platform.size.x - Enemy::SIZE.x);
+                self.enemies.push(Enemy::new(Vector2 { x: enemy_x, y: y_position + Enemy::SIZE.y }, kind));
             }
 
             self.platform_density = 1.0;
             self.platforms.push(platform);
+            self.stats.platforms_generated += 1;
             self.gen_heigt += y_spacing;
         }
+
+        if self.gen_heigt > start_height {
+            self.repair_unreachable();
+        }
+    }
+
+    // Sollte nie passieren, der Test generated_platforms_are_reachable prüft das. Falls doch, wird die Plattform über die nächste feste Plattform darunter gesetzt.
+    fn repair_unreachable(&mut self) {
+        for index in reachability::find_unreachable(&self.platforms, &self.player) {
+            let (pos, size) = (self.platforms[index].pos, self.platforms[index].size);
+            let Some(below_x) = self.platforms.iter().filter(|other| other.kind.is_solid() && other.pos.y < pos.y).max_by(|a, b| a.pos.y.total_cmp(&b.pos.y)).map(|below| below.pos.x) else { continue };
+            log::warn!("unreachable platform at height {} generated with seed {}, placing it above the platform below", pos.y, self.seed);
+
+            let mut item = self.platforms[index].item.take();
+            if let Some(item) = &mut item {
+                item.pos.x += below_x - pos.x;
+            }
+            self.platforms[index] = Platform::new(Vector2 { x: below_x, y: pos.y }, size, PlatformKind::Static, item);
+        }
+    }
+
+    pub fn update(&mut self, frame_time: f32) {
+        self.accumulator += frame_time.min(MAX_FRAME_TIME);
+
+        while self.accumulator >= TICK_TIME {
+            self.apply_replay();
+            self.step(TICK_TIME);
+            self.tick += 1;
+            self.accumulator -= TICK_TIME;
+        }
+
+        self.alpha = self.accumulator / TICK_TIME;
+    }
+
+    pub fn input(&mut self, input: Input) {
+        if self.replay.is_some() {
+            return;
+        }
+        self.recorder.record(self.tick, input);
+        self.apply_input(input);
+    }
+
+    fn apply_input(&mut self, input: Input) {
+        match input {
+            Input::MoveLeft(pressed) => {
+                if pressed {
+                    self.player.movement = -1.0;
+                } else if self.player.movement == -1.0 {
+                    self.player.movement = 0.0;
+                }
+            },
+            Input::MoveRight(pressed) => {
+                if pressed {
+                    self.player.movement = 1.0;
+                } else if self.player.movement == 1.0 {
+                    self.player.movement = 0.0;
+                }
+            },
+            Input::Steer(value) => self.player.movement = value.clamp(-1.0, 1.0),
+            Input::Restart(seed) => self.restart_with_seed(seed),
+            Input::Difficulty(index) => self.set_difficulty(index),
+            Input::Wrap(wrap) => self.set_wrap(wrap),
+        }
+    }
+
+    fn apply_replay(&mut self) {
+        let Some(mut replay) = self.replay.take() else { return };
+
+        while let Some(input) = replay.next(self.tick) {
+            self.recorder.record(self.tick, input);
+            self.apply_input(input);
+        }
+
+        if !replay.is_finished() {
+            self.replay = Some(replay);
+        }
+    }
+
+    pub fn start_replay(&mut self, replay: Replay) {
+        self.set_difficulty(replay.difficulty);
+        self.set_wrap(replay.wrap);
+        self.restart_with_seed(replay.seed);
+        self.tick = 0;
+        self.run_start_tick = 0;
+        self.accumulator = 0.0;
+        self.recorder = Recorder::new(replay.seed, self.difficulty, self.wrap);
+        self.replay = Some(replay);
+    }
+
+    // Zwischen zwei Ticks interpolierte View- und Projektionsmatrix für den Renderer
+    #[inline]
+    pub fn camera_matrices(&self) -> (Matrix4<f32>, Matrix4<f32>) {
+        self.camera.matrices(self.alpha)
     }
 
-    pub fn update(&mut self, delta_time: f32) {
+    fn step(&mut self, delta_time: f32) {
+        // Vor allem anderen, denn nach einer Landung werden die übrigen Plattformen in diesem Tick nicht mehr bewegt
+        for platform in &mut self.platforms {
+            platform.prev_pos = platform.pos;
+        }
+        for enemy in &mut self.enemies {
+            enemy.prev_pos = enemy.pos;
+        }
 
-        if delta_time > 1.0 || self.dead {
+        if self.dead {
             return;
         }
 
@@ -123,34 +311,47 @@ impl World {
 
         self.player.update(delta_time);
 
-        if self.player.pos.y >= self.score as f32 {
-            self.score = self.player.pos.y as u32;
-            let mut ui = self.ui.borrow_mut();
-            let score_element = ui.get_element(vec![0, 0]).unwrap();
-            match &score_element.inherit {
-                UiType::Text(text) => unsafe { (text as *const Text as *mut Text).as_mut().unwrap_unchecked().set_text((score_element as *const UiElement as *mut UiElement).as_mut().unwrap_unchecked(), &self.score.to_string()) },
-                _ => (),
-            };
-            ui.dirty = true;
+        if self.player.pos.y >= self.max_height {
+            self.score_progress += (self.player.pos.y - self.max_height) * self.player.effects.score_multiplier();
+            self.max_height = self.player.pos.y;
+            self.score = self.score_progress as u32;
+            self.hud.score.set_text(&self.score.to_string());
         } else if self.player.velocity.y < 0.0 && self.player.pos.y < self.view_start as f32 {
-            self.dead = true;
-            let mut ui = self.ui.borrow_mut();
-            {
-                let dead_text = unsafe { ui.get_element_mut(vec![1]).unwrap() };
-                dead_text.visible = true;
-                dead_text.dirty = true;
-            }
-            {
-                let restart_button = unsafe { ui.get_element_mut(vec![2]).unwrap() };
-                restart_button.visible = true;
-                restart_button.dirty = true;
+            self.die();
+        }
+
+        for enemy in &mut self.enemies {
+            enemy.update(delta_time);
+        }
+
+        for pickup in &mut self.pickups {
+            pickup.animation.update(delta_time);
+        }
+        self.pickups.retain(|pickup| !pickup.animation.finished());
+
+        if let Some(index) = self.enemies.iter().position(|enemy| self.player.overlaps(enemy.pos, enemy.size)) {
+            if self.player.stomps(prev_pos, &self.enemies[index]) {
+                self.enemies.remove(index);
+                self.player.jump();
+                self.stats.enemies_killed += 1;
+                self.camera.add_trauma(STOMP_TRAUMA);
+            } else if self.player.effects.jetpack > 0.0 {
+                self.enemies.remove(index);
+                self.stats.enemies_killed += 1;
+            } else if self.player.effects.shield > 0.0 {
+                self.enemies.remove(index);
+                self.player.effects.shield = 0.0;
+                self.camera.add_trauma(HIT_TRAUMA);
+            } else {
+                self.camera.add_trauma(HIT_TRAUMA);
+                self.die();
+                return;
             }
-            ui.dirty = true;
         }
 
-        self.smooth_view(delta_time, 15.0);
+        self.camera.update(delta_time, self.view_start as f32, self.player.velocity.y);
 
-        let step = self.player.pos.y - 300.0;
+        let step = self.player.pos.y - HEIGHT * 0.5;
         if step > self.view_start as f32 {
             self.view_end = self.view_end - self.view_start + step as u32;
             self.view_start = step as u32;
@@ -162,54 +363,157 @@ impl World {
 
         for platform in &mut self.platforms {
             platform.update(delta_time);
+            if platform.gone {
+                continue;
+            }
             if let Some(item) = &platform.item {
-                if self.player.collides_with_item(item) {
-                    if item.typ == 1{
-                        self.player.jump_with_strenght(800.0);
+                if item.kind == ItemKind::Spring {
+                    if self.player.collides_with_item(item) {
+                        item.apply(&mut self.player);
+                        self.stats.items_collected += 1;
+                        break;
                     }
-                    break;
+                } else if self.player.overlaps(item.pos, item.size) {
+                    item.apply(&mut self.player);
+                    self.pickups.push(Pickup::new(item));
+                    platform.item = None;
+                    self.stats.items_collected += 1;
                 }
-            } 
+            }
             
-            if self.player.collides_with_platform(prev_pos, platform) {
+            if !platform.broken && self.player.collides_with_platform(prev_pos, platform) && platform.land() {
+                self.camera.add_trauma((-self.player.velocity.y / LANDING_TRAUMA_SPEED).min(MAX_LANDING_TRAUMA));
                 self.player.jump();
                 break;
             }
         }
+
+        self.update_effects_text();
+    }
+
+    // Das HUD wird nur neu aufgebaut, wenn sich die angezeigten Sekunden ändern
+    fn update_effects_text(&mut self) {
+        let text = self.player.effects.describe();
+        if text == self.effects_text {
+            return;
+        }
+
+        self.hud.effects.set_visible(!text.is_empty());
+        if !text.is_empty() {
+            self.hud.effects.set_text(&text);
+        }
+        self.effects_text = text;
+    }
+
+    fn die(&mut self) {
+        self.dead = true;
+        self.stats.death_height = Some(self.player.pos.y);
+        self.finished_run = Some(ScoreEntry::new(self.score, self.seed, (self.tick - self.run_start_tick) as f32 * TICK_TIME));
+    }
+
+    pub fn restart(&mut self) {
+        if self.replay.is_some() {
+            return;
+        }
+        let seed = rand::random();
+        self.recorder.record(self.tick, Input::Restart(seed));
+        self.restart_with_seed(seed);
+    }
+
+    // Wechselt zum nächsten Preset, wirksam ab dem nächsten Neustart
+    pub fn next_difficulty(&mut self) {
+        if self.replay.is_some() {
+            return;
+        }
+        let index = ((self.difficulty as usize + 1) % self.difficulties.len()) as u8;
+        self.recorder.record(self.tick, Input::Difficulty(index));
+        self.set_difficulty(index);
+    }
+
+    fn set_difficulty(&mut self, index: u8) {
+        self.difficulty = index.min(self.difficulties.len() as u8 - 1);
+    }
+
+    // Wirkt sofort, auch mitten im Lauf
+    pub fn toggle_wrap(&mut self) {
+        if self.replay.is_some() {
+            return;
+        }
+        self.recorder.record(self.tick, Input::Wrap(!self.wrap));
+        self.set_wrap(!self.wrap);
+    }
+
+    fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+        self.player.wrap = wrap;
     }
 
     #[inline]
-    pub fn smooth_view(&mut self, delta_time: f32, smoothing_factor: f32) {
-        self.current_view += (self.view_start as f32 - self.current_view) * smoothing_factor * delta_time;
+    pub fn current_difficulty(&self) -> &Difficulty {
+        &self.difficulties[self.difficulty as usize]
     }
 
-    #[unsafe(no_mangle)]
-    pub fn restart(&mut self, ui: &mut UiState, _: &mut UiElement) {
-        
+    pub fn restart_with_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+        self.platform_density = 0.0;
         self.dead = false;
         self.score = 0;
+        self.max_height = 0.0;
+        self.score_progress = 0.0;
+        self.stats = RunStats::default();
+        self.run_start_tick = self.tick;
         self.gen_heigt = 75.0;
         self.platforms.clear();
+        self.enemies.clear();
+        self.pickups.clear();
         let view_scope = self.view_end - self.view_start;
         self.view_start = 0;
         self.view_end = view_scope;
-        self.player = Player::create(Vector2 { x: 185.0, y: 0.0 });
+        self.camera.reset(0.0);
+        self.player = Player::create(Vector2 { x: WIDTH * 0.5 - 15.0, y: 0.0 }, self.current_difficulty().physics, self.wrap);
 
-        self.platforms.push(Platform::new(Vector2 { x: 170.0, y: 50.0 }, Vector2 { x: 60.0, y: 12.0 }, 0, None));
+        self.platforms.push(Platform::new(Vector2 { x: WIDTH * 0.5 - 30.0, y: 50.0 }, Vector2 { x: 60.0, y: 12.0 }, PlatformKind::Static, None));
 
-        {
-            let dead_text = unsafe { ui.get_element_mut(vec![1]).unwrap() };
-            dead_text.visible = false;
-            dead_text.dirty = true;
-        }
-        {
-            let resart_button = unsafe { ui.get_element_mut(vec![2]).unwrap() };
-            resart_button.visible = false;
-            resart_button.dirty = true;
-        }
+        self.hud.score.set_text("0");
+        self.hud.seed.set_text(&format!("Seed: {seed}"));
+    }
+}
+
+#[test]
+fn fixed_timestep_is_frame_rate_independent() {
+    use super::{difficulty::default_presets, states::build_main};
 
-        ui.dirty = true;
+    let mut slow = World::create_with_seed(build_main(), 42, default_presets());
+    let mut fast = World::create_with_seed(build_main(), 42, default_presets());
+
+    while slow.tick < 1200 {
+        slow.update(1.0 / 30.0);
+    }
+    while fast.tick < slow.tick {
+        fast.update(1.0 / 144.0);
     }
 
+    assert_eq!(slow.player.pos, fast.player.pos);
+    assert_eq!(slow.score, fast.score);
+    assert_eq!(slow.platforms.len(), fast.platforms.len());
+}
 
-}
\ No newline at end of file
+#[test]
+fn generated_platforms_are_reachable() {
+    use super::{difficulty::default_presets, reachability::find_unreachable, states::build_main};
+
+    for (difficulty, wrap) in (0..default_presets().len() as u8).flat_map(|difficulty| [(difficulty, false), (difficulty, true)]) {
+        for seed in 0..20 {
+            let mut world = World::create_with_seed(build_main(), seed, default_presets());
+            world.difficulty = difficulty;
+            world.wrap = wrap;
+            world.restart_with_seed(seed);
+            world.score = 1_000_000;
+            world.view_end = 10_000;
+            world.generate_platforms();
+
+            assert!(find_unreachable(&world.platforms, &world.player).is_empty(), "unreachable platform with seed {seed} on difficulty {difficulty}, wrap {wrap}");
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::{collections::{BTreeSet, HashMap}, sync::{LazyLock, Mutex, PoisonError}};
+
+pub const TEXTURE: &[u8] = include_bytes!("../../textures/texture.png");
+const MANIFEST: &str = include_str!("../../textures/texture.atlas");
+
+static ATLAS: LazyLock<Atlas> = LazyLock::new(|| Atlas::parse(MANIFEST).unwrap_or_else(|err| panic!("textures/texture.atlas: {err}")));
+// Bereits gemeldete fehlende Sprites, sprite() wird in jedem Frame aufgerufen
+static MISSING: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+// Rechteck eines Sprites in der Textur in Pixeln
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Sprite {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Sprite {
+    // Der Shader erwartet beide Werte als ((high as u32) << 16) | (low as u32)
+    #[inline]
+    pub const fn uv_start(self) -> u32 {
+        ((self.y as u32) << 16) | self.x as u32
+    }
+
+    #[inline]
+    pub const fn uv_size(self) -> u32 {
+        ((self.height as u32) << 16) | self.width as u32
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Atlas {
+    sprites: HashMap<String, Sprite>,
+}
+
+impl Atlas {
+    // Eine Zeile pro Sprite: `name x y breite höhe`, Leerzeilen und Zeilen mit # werden übersprungen
+    pub fn parse(manifest: &str) -> Result<Self, String> {
+        let mut sprites = HashMap::new();
+        for (number, line) in manifest.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let name = parts.next().unwrap_or_default();
+            let values: Vec<u16> = parts.map(str::parse).collect::<Result<_, _>>().map_err(|err| format!("line {}: {err}", number + 1))?;
+            let [x, y, width, height] = values[..] else {
+                return Err(format!("line {}: expected `name x y width height`", number + 1));
+            };
+            if sprites.insert(name.to_owned(), Sprite { x, y, width, height }).is_some() {
+                return Err(format!("line {}: sprite {name} defined twice", number + 1));
+            }
+        }
+        Ok(Self { sprites })
+    }
+
+    pub fn get(&self, name: &str) -> Option<Sprite> {
+        self.sprites.get(name).copied()
+    }
+}
+
+// Sprite aus dem Manifest von texture.png. Fehlende Namen werden einmal geloggt und bleiben unsichtbar.
+pub fn sprite(name: &str) -> Sprite {
+    ATLAS.get(name).unwrap_or_else(|| {
+        if MISSING.lock().unwrap_or_else(PoisonError::into_inner).insert(name.to_string()) {
+            log::error!("sprite {name} is missing in texture.atlas");
+        }
+        Sprite::default()
+    })
+}
+
+// Größe von texture.png, ohne das Bild zu dekodieren
+pub fn texture_size() -> (u32, u32) {
+    let reader = png::Decoder::new(TEXTURE).read_info().expect("texture.png is not a valid png");
+    let info = reader.info();
+    (info.width, info.height)
+}
+
+#[test]
+fn manifest_covers_all_sprites_inside_texture() {
+    let (width, height) = texture_size();
+    for (name, sprite) in &ATLAS.sprites {
+        assert!(sprite.width > 0 && sprite.height > 0, "{name} is empty");
+        assert!(sprite.x as u32 + sprite.width as u32 <= width && sprite.y as u32 + sprite.height as u32 <= height, "{name} lies outside of texture.png");
+    }
+
+    let names = ["player", "platform", "platform_horizontal", "platform_vertical", "platform_breakable", "platform_broken", "platform_vanishing", "platform_oneshot", "item_spring", "item_jetpack", "item_shield", "item_multiplier", "enemy_stationary", "enemy_patrol", "enemy_hover"];
+    for name in names {
+        assert!(ATLAS.get(name).is_some(), "{name} is missing");
+    }
+    let packed = Sprite { x: 32, y: 8, width: 128, height: 256 };
+    assert_eq!((packed.uv_start(), packed.uv_size()), ((8 << 16) | 32, (256 << 16) | 128));
+
+    assert!(Atlas::parse("player 1 2 3").is_err());
+    assert!(Atlas::parse("player 1 2 3 4\nplayer 1 2 3 4").is_err());
+}
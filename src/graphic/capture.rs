@@ -0,0 +1,142 @@
+use std::{fs::File, io::BufWriter, path::{Path, PathBuf}, sync::{mpsc::{self, SendError, Sender}, LazyLock}, thread, time::{SystemTime, UNIX_EPOCH}};
+
+use ash::vk;
+
+use super::image::COLOR_RANGE;
+
+// Ausstehende Aufnahme: ein einzelner Screenshot oder eine Serie über mehrere Frames
+#[derive(Debug, Clone)]
+pub struct Capture {
+    pub remaining: u32,
+    // Für Serien, damit die Dateien in Bildreihenfolge sortiert bleiben
+    pub index: Option<u32>,
+    // Zeichnet die Aufnahme-Frames ohne UI, etwa wenn das Pausenmenü offen ist
+    pub hide_ui: bool,
+    dir: PathBuf,
+    stem: String,
+}
+
+impl Capture {
+    pub fn screenshot(dir: PathBuf, hide_ui: bool) -> Self {
+        Self { remaining: 1, index: None, hide_ui, dir, stem: timestamp_stem("screenshot") }
+    }
+
+    pub fn burst(dir: PathBuf, frames: u32) -> Self {
+        Self { remaining: frames.max(1), index: Some(0), hide_ui: false, dir, stem: timestamp_stem("burst") }
+    }
+
+    // Pfad für den nächsten Frame, zählt die Serie weiter
+    pub fn next_path(&mut self) -> PathBuf {
+        self.remaining = self.remaining.saturating_sub(1);
+        match &mut self.index {
+            Some(index) => {
+                *index += 1;
+                self.dir.join(format!("{}_{:04}.png", self.stem, index))
+            },
+            None => self.dir.join(format!("{}.png", self.stem)),
+        }
+    }
+}
+
+fn timestamp_stem(prefix: &str) -> String {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_millis());
+    format!("{prefix}_{millis}")
+}
+
+// Bringt die Pixel eines 8-Bit-Swapchain-Formats in RGBA. Das Fenster ist undurchsichtig, also auch das Bild.
+// Ob UNORM oder SRGB, die Bytes sind das, was der Bildschirm zeigt, und damit bereits sRGB-kodiert.
+pub fn to_rgba(format: vk::Format, mut pixels: Vec<u8>) -> Option<Vec<u8>> {
+    let bgra = match format {
+        vk::Format::R8G8B8A8_SRGB | vk::Format::R8G8B8A8_UNORM => false,
+        vk::Format::B8G8R8A8_SRGB | vk::Format::B8G8R8A8_UNORM => true,
+        _ => return None,
+    };
+
+    for pixel in pixels.chunks_exact_mut(4) {
+        if bgra {
+            pixel.swap(0, 2);
+        }
+        pixel[3] = u8::MAX;
+    }
+    Some(pixels)
+}
+
+type PngJob = (PathBuf, u32, u32, Vec<u8>);
+
+// Ein einzelner Thread kodiert alle Aufnahmen nacheinander im Hintergrund, damit Serien den Spielablauf nicht ausbremsen
+static PNG_WRITER: LazyLock<Sender<PngJob>> = LazyLock::new(|| {
+    let (sender, receiver) = mpsc::channel::<PngJob>();
+    thread::Builder::new().name("png writer".to_string()).spawn(move || {
+        for (path, width, height, rgba) in receiver {
+            match write_png(&path, width, height, &rgba) {
+                Ok(()) => log::info!("saved {}", path.display()),
+                Err(err) => log::error!("could not save {}: {err}", path.display()),
+            }
+        }
+    }).expect("could not start png writer thread");
+    sender
+});
+
+pub fn save_png(path: PathBuf, width: u32, height: u32, rgba: Vec<u8>) {
+    if let Err(SendError((path, ..))) = PNG_WRITER.send((path, width, height, rgba)) {
+        log::error!("png writer is gone, could not save {}", path.display());
+    }
+}
+
+fn write_png(path: &Path, width: u32, height: u32, rgba: &[u8]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+    }
+    let file = File::create(path).map_err(|err| err.to_string())?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual);
+    encoder.write_header().and_then(|mut writer| writer.write_image_data(rgba)).map_err(|err| err.to_string())
+}
+
+// Kopiert ein präsentierbares Swapchain-Bild nach dem Render-Pass in `buffer` und gibt es danach wieder zum Anzeigen frei
+pub fn record_copy(device: &ash::Device, cmd_buf: vk::CommandBuffer, image: vk::Image, extent: vk::Extent2D, buffer: vk::Buffer) {
+    let barrier = |old_layout, new_layout, src_access_mask, dst_access_mask| vk::ImageMemoryBarrier {
+        src_access_mask,
+        dst_access_mask,
+        old_layout,
+        new_layout,
+        src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+        dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+        image,
+        subresource_range: COLOR_RANGE,
+        ..Default::default()
+    };
+
+    let region = vk::BufferImageCopy {
+        buffer_offset: 0,
+        buffer_row_length: 0,
+        buffer_image_height: 0,
+        image_subresource: vk::ImageSubresourceLayers { aspect_mask: vk::ImageAspectFlags::COLOR, mip_level: 0, base_array_layer: 0, layer_count: 1 },
+        image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
+        image_extent: vk::Extent3D { width: extent.width, height: extent.height, depth: 1 },
+    };
+
+    let to_transfer = barrier(vk::ImageLayout::PRESENT_SRC_KHR, vk::ImageLayout::TRANSFER_SRC_OPTIMAL, vk::AccessFlags::COLOR_ATTACHMENT_WRITE, vk::AccessFlags::TRANSFER_READ);
+    let to_present = barrier(vk::ImageLayout::TRANSFER_SRC_OPTIMAL, vk::ImageLayout::PRESENT_SRC_KHR, vk::AccessFlags::TRANSFER_READ, vk::AccessFlags::empty());
+
+    unsafe {
+        device.cmd_pipeline_barrier(cmd_buf, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[], &[], &[to_transfer]);
+        device.cmd_copy_image_to_buffer(cmd_buf, image, vk::ImageLayout::TRANSFER_SRC_OPTIMAL, buffer, &[region]);
+        device.cmd_pipeline_barrier(cmd_buf, vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::BOTTOM_OF_PIPE, vk::DependencyFlags::empty(), &[], &[], &[to_present]);
+    }
+}
+
+#[test]
+fn swapchain_pixels_become_opaque_rgba() {
+    let bgra = vec![1, 2, 3, 0, 10, 20, 30, 128];
+    assert_eq!(to_rgba(vk::Format::B8G8R8A8_SRGB, bgra.clone()), Some(vec![3, 2, 1, 255, 30, 20, 10, 255]));
+    assert_eq!(to_rgba(vk::Format::R8G8B8A8_UNORM, bgra.clone()), Some(vec![1, 2, 3, 255, 10, 20, 30, 255]));
+    assert_eq!(to_rgba(vk::Format::A2B10G10R10_UNORM_PACK32, bgra), None);
+
+    let mut burst = Capture::burst(PathBuf::from("shots"), 2);
+    assert!(burst.next_path().to_string_lossy().ends_with("_0001.png"));
+    assert!(burst.next_path().to_string_lossy().ends_with("_0002.png"));
+    assert_eq!(burst.remaining, 0);
+}
@@ -1,15 +1,74 @@
-#![allow(unused)]
-
 use ash::vk;
+use iron_oxide::graphics::VkBase;
 
+// Geräteeigenes 2D-Bild mit View für Ziele, die iron_oxide nicht anlegt, etwa das Offscreen-Farbziel
 pub struct Image {
-    texture_image: vk::Image,
-    texture_image_mem: vk::DeviceMemory,
-    texture_image_view: vk::ImageView,
+    pub image: vk::Image,
+    pub memory: vk::DeviceMemory,
+    pub view: vk::ImageView,
+    pub extent: vk::Extent2D,
 }
 
 impl Image {
-    pub fn new() {
-        //let ll = vk::ImageView::null();
+    pub fn create(base: &VkBase, extent: vk::Extent2D, format: vk::Format, usage: vk::ImageUsageFlags) -> Self {
+        let device = &base.device;
+
+        let image_info = vk::ImageCreateInfo {
+            image_type: vk::ImageType::TYPE_2D,
+            format,
+            extent: vk::Extent3D { width: extent.width, height: extent.height, depth: 1 },
+            mip_levels: 1,
+            array_layers: 1,
+            samples: vk::SampleCountFlags::TYPE_1,
+            tiling: vk::ImageTiling::OPTIMAL,
+            usage,
+            sharing_mode: vk::SharingMode::EXCLUSIVE,
+            initial_layout: vk::ImageLayout::UNDEFINED,
+            ..Default::default()
+        };
+        let image = unsafe { device.create_image(&image_info, None).unwrap() };
+
+        let requirements = unsafe { device.get_image_memory_requirements(image) };
+        let alloc_info = vk::MemoryAllocateInfo {
+            allocation_size: requirements.size,
+            memory_type_index: find_memory_type(base, requirements.memory_type_bits, vk::MemoryPropertyFlags::DEVICE_LOCAL),
+            ..Default::default()
+        };
+        let memory = unsafe { device.allocate_memory(&alloc_info, None).unwrap() };
+        unsafe { device.bind_image_memory(image, memory, 0).unwrap() };
+
+        let view_info = vk::ImageViewCreateInfo {
+            image,
+            view_type: vk::ImageViewType::TYPE_2D,
+            format,
+            subresource_range: COLOR_RANGE,
+            ..Default::default()
+        };
+        let view = unsafe { device.create_image_view(&view_info, None).unwrap() };
+
+        Self { image, memory, view, extent }
     }
-}
\ No newline at end of file
+
+    pub fn destroy(&self, device: &ash::Device) {
+        unsafe {
+            device.destroy_image_view(self.view, None);
+            device.destroy_image(self.image, None);
+            device.free_memory(self.memory, None);
+        }
+    }
+}
+
+pub const COLOR_RANGE: vk::ImageSubresourceRange = vk::ImageSubresourceRange {
+    aspect_mask: vk::ImageAspectFlags::COLOR,
+    base_mip_level: 0,
+    level_count: 1,
+    base_array_layer: 0,
+    layer_count: 1,
+};
+
+fn find_memory_type(base: &VkBase, type_bits: u32, properties: vk::MemoryPropertyFlags) -> u32 {
+    let memory_properties = unsafe { base.instance.get_physical_device_memory_properties(base.physical_device) };
+    (0..memory_properties.memory_type_count)
+        .find(|&index| type_bits & (1 << index) != 0 && memory_properties.memory_types[index as usize].property_flags.contains(properties))
+        .expect("no suitable memory type")
+}
@@ -3,12 +3,17 @@ use std::mem::offset_of;
 use ash::vk;
 use cgmath::Vector2;
 
+use super::atlas::Sprite;
+
+// Reihenfolge und Offsets wie in Vertex::GET_ATTRIBUTE_DESCRIPTIONS
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
+#[repr(C)]
 pub struct InstanceData {
-    id: u32,
     position: Vector2<f32>,
     size: Vector2<f32>,
+    // Spalten und Zeilen, wie oft das Sprite über die Fläche wiederholt wird, gepackt wie uv_size
+    repeat: u32,
     uv_start: u32,
     uv_size: u32,
 }
@@ -32,7 +37,7 @@ impl InstanceData {
             binding: 0,
             location: 5,
             format: vk::Format::R32_UINT,
-            offset: offset_of!(InstanceData, id) as _,
+            offset: offset_of!(InstanceData, repeat) as _,
         },
         vk::VertexInputAttributeDescription {
             binding: 0,
@@ -47,7 +52,19 @@ impl InstanceData {
             offset: offset_of!(InstanceData, uv_size) as _,
         },
     ];
-    pub const fn new(position: Vector2<f32>, size: Vector2<f32>, id: u32, uv_start: u32, uv_size: u32) -> Self {
-        Self { position, size, id, uv_start, uv_size }
+    pub const fn new(position: Vector2<f32>, size: Vector2<f32>, repeat: u32, uv_start: u32, uv_size: u32) -> Self {
+        Self { position, size, repeat, uv_start, uv_size }
+    }
+
+    #[inline]
+    pub const fn from_sprite(position: Vector2<f32>, size: Vector2<f32>, sprite: Sprite) -> Self {
+        Self::tiled(position, size, (1, 1), sprite)
+    }
+
+    // `tile` ist die Größe einer Kachel, die ganze Fläche ist `columns` mal `rows` Kacheln groß
+    #[inline]
+    pub const fn tiled(position: Vector2<f32>, tile: Vector2<f32>, (columns, rows): (u16, u16), sprite: Sprite) -> Self {
+        let size = Vector2 { x: tile.x * columns as f32, y: tile.y * rows as f32 };
+        Self::new(position, size, ((rows as u32) << 16) | columns as u32, sprite.uv_start(), sprite.uv_size())
     }
 }
\ No newline at end of file
@@ -0,0 +1,68 @@
+use std::{array, mem::size_of, ptr};
+
+use ash::vk;
+use iron_oxide::graphics::{self, VkBase};
+
+use super::{sprite_batch::SpriteBatch, vulkan_render::MAXFRAMESINFLIGHT, InstanceData};
+
+// Platz für die ersten Frames, danach wächst der Puffer mit der Welt
+const INITIAL_CAPACITY: usize = 256;
+
+// Ein dauerhaft gemappter Vertex-Puffer je Frame in Flight, wie bei den Uniform-Buffern.
+// Jeder Frame schreibt nur in seinen eigenen Puffer, den die GPU nach dem Warten auf seine Fence nicht mehr liest.
+pub struct InstanceBuffer {
+    frames: [FrameBuffer; MAXFRAMESINFLIGHT],
+}
+
+struct FrameBuffer {
+    buffer: graphics::Buffer,
+    mapped: *mut InstanceData,
+    capacity: usize,
+}
+
+impl FrameBuffer {
+    fn create(base: &VkBase, capacity: usize) -> Self {
+        let size = (capacity * size_of::<InstanceData>()) as u64;
+        let buffer = graphics::Buffer::create(base, size, vk::BufferUsageFlags::VERTEX_BUFFER, vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT);
+        let mapped = buffer.map_memory(&base.device, size) as *mut InstanceData;
+        Self { buffer, mapped, capacity }
+    }
+
+    fn destroy(&self, device: &ash::Device) {
+        self.buffer.unmap_memory(device);
+        self.buffer.destroy(device);
+    }
+}
+
+impl InstanceBuffer {
+    pub fn create(base: &VkBase) -> Self {
+        Self { frames: array::from_fn(|_| FrameBuffer::create(base, INITIAL_CAPACITY)) }
+    }
+
+    #[inline]
+    pub fn buffer(&self, frame: usize) -> vk::Buffer {
+        self.frames[frame].buffer.inner
+    }
+
+    // Schreibt die Ebenen des Batches hintereinander in den Puffer von `frame`. Passt der Frame nicht hinein,
+    // wird nur dieser Puffer größer angelegt, die anderen wachsen erst, wenn sie selbst zu klein sind.
+    pub fn write(&mut self, base: &VkBase, frame: usize, batch: &SpriteBatch) -> u32 {
+        let len = batch.len();
+        let target = &mut self.frames[frame];
+        if len > target.capacity {
+            target.destroy(&base.device);
+            *target = FrameBuffer::create(base, len.next_power_of_two());
+        }
+
+        let mut offset = 0;
+        for layer in batch.layers() {
+            unsafe { ptr::copy_nonoverlapping(layer.as_ptr(), target.mapped.add(offset), layer.len()) };
+            offset += layer.len();
+        }
+        len as u32
+    }
+
+    pub fn destroy(&self, device: &ash::Device) {
+        self.frames.iter().for_each(|frame| frame.destroy(device));
+    }
+}
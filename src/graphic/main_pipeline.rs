@@ -2,7 +2,7 @@
 use ash::vk::{self};
 use super::{shader_modul, Vertex};
 
-pub fn create_main_pipeline(device: &ash::Device, window_size: winit::dpi::PhysicalSize<u32>, render_pass: vk::RenderPass, descriptor_set_layout: &vk::DescriptorSetLayout) -> (vk::PipelineLayout, vk::Pipeline) {
+pub fn create_main_pipeline(device: &ash::Device, window_size: winit::dpi::PhysicalSize<u32>, render_pass: vk::RenderPass, descriptor_set_layout: &vk::DescriptorSetLayout, atlas_size: (u32, u32)) -> (vk::PipelineLayout, vk::Pipeline) {
     let vertex_shader_buff= include_bytes!("../../shaders/vert.spv");
     let fragment_shader_buff = include_bytes!("../../shaders/frag.spv");
 
@@ -22,11 +22,26 @@ pub fn create_main_pipeline(device: &ash::Device, window_size: winit::dpi::Physi
         ..Default::default()
     };
 
+    // ATLAS_WIDTH und ATLAS_HEIGHT im Fragment-Shader, damit die UVs zur tatsächlichen Texturgröße passen
+    let atlas_size = [atlas_size.0 as f32, atlas_size.1 as f32];
+    let specialization_entries = [
+        vk::SpecializationMapEntry { constant_id: 0, offset: 0, size: size_of::<f32>() },
+        vk::SpecializationMapEntry { constant_id: 1, offset: size_of::<f32>() as u32, size: size_of::<f32>() },
+    ];
+    let specialization_info = vk::SpecializationInfo {
+        map_entry_count: specialization_entries.len() as _,
+        p_map_entries: specialization_entries.as_ptr(),
+        data_size: size_of_val(&atlas_size),
+        p_data: atlas_size.as_ptr() as *const _,
+        ..Default::default()
+    };
+
     let fragment_stage_info = vk::PipelineShaderStageCreateInfo {
         s_type: vk::StructureType::PIPELINE_SHADER_STAGE_CREATE_INFO,
         stage: vk::ShaderStageFlags::FRAGMENT,
         module: fragment_shader_module,
         p_name:  b"main\0".as_ptr() as *const _,
+        p_specialization_info: &specialization_info,
         ..Default::default()
     };
 
@@ -4,12 +4,18 @@ mod uniform_buffer_object;
 mod buffer;
 mod vertex;
 mod instance;
+mod instance_buffer;
+mod sprite_batch;
 mod main_pipeline;
 mod shader_modul;
 mod image;
+mod offscreen;
+pub mod capture;
+pub mod atlas;
 
 pub use vulkan_render::VulkanRender;
 pub use swapchain::Swapchain;
 pub use uniform_buffer_object::UniformBufferObject;
 pub use vertex::Vertex;
 pub use instance::InstanceData;
+pub use sprite_batch::{Layer, SpriteBatch};
@@ -0,0 +1,172 @@
+use std::ptr;
+
+use ash::{ext::debug_utils, khr::surface, vk};
+use iron_oxide::graphics::{self, SinlgeTimeCommands, VkBase};
+
+use super::image::{Image, COLOR_RANGE};
+
+// Farbziel ohne Swapchain. Der Renderer zeichnet hinein wie in ein Swapchain-Bild, danach wird es in einen Host-Puffer kopiert.
+pub struct Offscreen {
+    pub image: Image,
+    readback: graphics::Buffer,
+}
+
+impl Offscreen {
+    // RGBA statt des BGRA der meisten Swapchains, damit die Pixel ohne Umsortieren als PNG passen
+    pub const FORMAT: vk::Format = vk::Format::R8G8B8A8_SRGB;
+
+    pub fn create(base: &VkBase, extent: vk::Extent2D) -> Self {
+        let image = Image::create(base, extent, Self::FORMAT, vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::TRANSFER_SRC);
+        let readback = graphics::Buffer::create(base, Self::byte_size(extent), vk::BufferUsageFlags::TRANSFER_DST, vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT);
+        Self { image, readback }
+    }
+
+    #[inline]
+    const fn byte_size(extent: vk::Extent2D) -> u64 {
+        extent.width as u64 * extent.height as u64 * 4
+    }
+
+    // Kopiert das zuletzt gezeichnete Bild zurück. Die Pixel liegen Zeile für Zeile von oben, ohne Lücken.
+    pub fn read_pixels(&self, base: &VkBase, command_pool: &vk::CommandPool) -> Vec<u8> {
+        let extent = self.image.extent;
+        let size = Self::byte_size(extent);
+
+        let barrier = vk::ImageMemoryBarrier {
+            src_access_mask: vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+            dst_access_mask: vk::AccessFlags::TRANSFER_READ,
+            old_layout: vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+            new_layout: vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+            dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+            image: self.image.image,
+            subresource_range: COLOR_RANGE,
+            ..Default::default()
+        };
+        let region = vk::BufferImageCopy {
+            buffer_offset: 0,
+            buffer_row_length: 0,
+            buffer_image_height: 0,
+            image_subresource: vk::ImageSubresourceLayers { aspect_mask: vk::ImageAspectFlags::COLOR, mip_level: 0, base_array_layer: 0, layer_count: 1 },
+            image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
+            image_extent: vk::Extent3D { width: extent.width, height: extent.height, depth: 1 },
+        };
+
+        let cmd_buf = SinlgeTimeCommands::begin(base, command_pool);
+        unsafe {
+            base.device.cmd_pipeline_barrier(cmd_buf, vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[], &[], &[barrier]);
+            base.device.cmd_copy_image_to_buffer(cmd_buf, self.image.image, vk::ImageLayout::TRANSFER_SRC_OPTIMAL, self.readback.inner, &[region]);
+        }
+        SinlgeTimeCommands::end(base, command_pool, cmd_buf);
+
+        let mut pixels = vec![0; size as usize];
+        let mapped = self.readback.map_memory(&base.device, size);
+        unsafe { ptr::copy_nonoverlapping(mapped as *const u8, pixels.as_mut_ptr(), pixels.len()) };
+        self.readback.unmap_memory(&base.device);
+
+        pixels
+    }
+
+    pub fn destroy(&self, device: &ash::Device) {
+        self.image.destroy(device);
+        self.readback.destroy(device);
+    }
+}
+
+// VkBase ohne Fenster: keine Surface, keine Swapchain-Erweiterung und kein Debug-Messenger.
+// Die Loader für Surface und Debug-Utils werden nur angelegt, damit VkBase vollständig ist, und nie aufgerufen.
+pub fn create_headless_base() -> VkBase {
+    #[cfg(feature = "linked")]
+    let entry = ash::Entry::linked();
+    #[cfg(not(feature = "linked"))]
+    let entry = unsafe { ash::Entry::load() }.expect("could not load the vulkan library");
+
+    let app_info = vk::ApplicationInfo {
+        p_application_name: c"vudeljump".as_ptr(),
+        api_version: vk::API_VERSION_1_0,
+        ..Default::default()
+    };
+    let instance_info = vk::InstanceCreateInfo {
+        p_application_info: &app_info,
+        ..Default::default()
+    };
+    let instance = unsafe { entry.create_instance(&instance_info, None) }.expect("could not create a vulkan instance");
+
+    // Erstes Gerät mit einer Grafik-Queue, auf CI ist das meist lavapipe
+    let (physical_device, queue_family_index) = unsafe { instance.enumerate_physical_devices() }.expect("could not enumerate vulkan devices").into_iter().find_map(|device| {
+        let families = unsafe { instance.get_physical_device_queue_family_properties(device) };
+        families.iter().position(|family| family.queue_flags.contains(vk::QueueFlags::GRAPHICS)).map(|index| (device, index as u32))
+    }).expect("no vulkan device with a graphics queue");
+
+    let priority = 1.0;
+    let queue_info = vk::DeviceQueueCreateInfo {
+        queue_family_index,
+        queue_count: 1,
+        p_queue_priorities: &priority,
+        ..Default::default()
+    };
+    let device_info = vk::DeviceCreateInfo {
+        queue_create_info_count: 1,
+        p_queue_create_infos: &queue_info,
+        ..Default::default()
+    };
+    let device = unsafe { instance.create_device(physical_device, &device_info, None) }.expect("could not create a vulkan device");
+    let queue = unsafe { device.get_device_queue(queue_family_index, 0) };
+
+    let surface_loader = surface::Instance::new(&entry, &instance);
+    let debug_utils = debug_utils::Instance::new(&entry, &instance);
+
+    VkBase {
+        entry,
+        instance,
+        device,
+        physical_device,
+        queue,
+        queue_family_index,
+        surface: vk::SurfaceKHR::null(),
+        surface_loader,
+        debug_utils,
+        utils_messenger: vk::DebugUtilsMessengerEXT::null(),
+    }
+}
+
+#[test]
+#[ignore = "braucht einen Vulkan-Treiber, auf CI lavapipe, siehe .github/workflows/golden.yml"]
+fn world_and_hud_match_golden_image() {
+    use winit::dpi::PhysicalSize;
+
+    use crate::game::{difficulty::default_presets, playfield, states::build_main, World};
+    use super::VulkanRender;
+
+    const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/world_and_hud.png");
+    // Software- und GPU-Treiber runden an Kanten unterschiedlich
+    const CHANNEL_TOLERANCE: u8 = 2;
+    const MAX_DIFFERENT_PIXELS: usize = 200;
+
+    let size = PhysicalSize::new(playfield::WIDTH as u32, playfield::HEIGHT as u32);
+
+    let mut world = World::create_with_seed(build_main(), 1, default_presets());
+    world.update(0.5);
+    let mut renderer = VulkanRender::create_offscreen(&world, size);
+    renderer.attach_ui(world.hud.ui.clone());
+    let pixels = renderer.render_offscreen();
+
+    // Mit VUDELJUMP_BLESS=1 oder ohne vorhandenes Bild wird das aktuelle Ergebnis zur neuen Referenz
+    let bless = std::env::var_os("VUDELJUMP_BLESS").is_some();
+    if bless || !std::path::Path::new(GOLDEN).exists() {
+        std::fs::create_dir_all(std::path::Path::new(GOLDEN).parent().unwrap()).unwrap();
+        let mut encoder = png::Encoder::new(std::fs::File::create(GOLDEN).unwrap(), size.width, size.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header().unwrap().write_image_data(&pixels).unwrap();
+        assert!(bless, "wrote new golden image {GOLDEN}, check and commit it");
+        return;
+    }
+
+    let mut reader = png::Decoder::new(std::fs::File::open(GOLDEN).unwrap()).read_info().unwrap();
+    let mut golden = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut golden).unwrap();
+    assert_eq!((info.width, info.height), (size.width, size.height));
+
+    let different = pixels.chunks_exact(4).zip(golden.chunks_exact(4)).filter(|(a, b)| a.iter().zip(b.iter()).any(|(a, b)| a.abs_diff(*b) > CHANNEL_TOLERANCE)).count();
+    assert!(different <= MAX_DIFFERENT_PIXELS, "{different} pixels differ from {GOLDEN}");
+}
@@ -0,0 +1,71 @@
+use cgmath::Vector2;
+
+use super::{atlas::Sprite, InstanceData};
+
+// Zeichenebenen von hinten nach vorn, innerhalb einer Ebene zählt die Reihenfolge der Aufrufe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Layer {
+    Background,
+    Platforms,
+    Items,
+    Enemies,
+    Player,
+    Particles,
+}
+
+impl Layer {
+    pub const COUNT: usize = 6;
+}
+
+// Sammelt die Sprites eines Frames nach Ebenen. Die Listen behalten ihre Kapazität, damit pro Frame nichts angelegt wird.
+#[derive(Debug, Default)]
+pub struct SpriteBatch {
+    layers: [Vec<InstanceData>; Layer::COUNT],
+}
+
+impl SpriteBatch {
+    pub fn clear(&mut self) {
+        self.layers.iter_mut().for_each(Vec::clear);
+    }
+
+    // `position` ist die linke obere Ecke in Weltkoordinaten mit y nach unten, wie sie der Shader erwartet
+    #[inline]
+    pub fn draw(&mut self, layer: Layer, position: Vector2<f32>, size: Vector2<f32>, sprite: Sprite) {
+        self.layers[layer as usize].push(InstanceData::from_sprite(position, size, sprite));
+    }
+
+    // Wiederholt das Sprite `repeat` = (Spalten, Zeilen) mal ab `position`, für Hintergründe aus einem Stück
+    #[inline]
+    pub fn draw_tiled(&mut self, layer: Layer, position: Vector2<f32>, tile: Vector2<f32>, repeat: (u16, u16), sprite: Sprite) {
+        self.layers[layer as usize].push(InstanceData::tiled(position, tile, repeat, sprite));
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.iter().map(Vec::len).sum()
+    }
+
+    // Die Ebenen in Zeichenreihenfolge, zusammen ergeben sie den Inhalt des Instanzpuffers
+    #[inline]
+    pub fn layers(&self) -> impl Iterator<Item = &[InstanceData]> {
+        self.layers.iter().map(Vec::as_slice)
+    }
+}
+
+#[test]
+fn layers_draw_back_to_front() {
+    let sprite = |x| Sprite { x, ..Default::default() };
+    let size = Vector2 { x: 1.0, y: 1.0 };
+    let mut batch = SpriteBatch::default();
+
+    batch.draw(Layer::Player, Vector2 { x: 0.0, y: 0.0 }, size, sprite(1));
+    batch.draw(Layer::Platforms, Vector2 { x: 0.0, y: 0.0 }, size, sprite(2));
+    batch.draw(Layer::Particles, Vector2 { x: 0.0, y: 0.0 }, size, sprite(3));
+    batch.draw(Layer::Platforms, Vector2 { x: 0.0, y: 0.0 }, size, sprite(4));
+
+    let expected = [2, 4, 1, 3].map(|x| InstanceData::from_sprite(Vector2 { x: 0.0, y: 0.0 }, size, sprite(x)));
+    assert!(batch.layers().flatten().eq(expected.iter()));
+    assert_eq!(batch.len(), 4);
+
+    batch.clear();
+    assert_eq!(batch.len(), 0);
+}
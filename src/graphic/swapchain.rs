@@ -6,6 +6,8 @@ use winit::dpi::PhysicalSize;
 pub struct Swapchain {
     pub loader: swapchain::Device,
     pub swapchain: SwapchainKHR,
+    // Für Screenshots, die Views zeigen auf dieselben Bilder
+    pub images: Vec<vk::Image>,
     pub image_views: Vec<ImageView>,
     pub capabilities: SurfaceCapabilitiesKHR,
     pub format: SurfaceFormatKHR,
@@ -23,11 +25,13 @@ impl Swapchain {
             vk::CompositeAlphaFlagsKHR::INHERIT
         };
         let swapchain = Self::create_swap_chain(window_size, &base.surface, &loader, &capabilities, composite_alpha, format, present_mode, base.queue_family_index);
-        let image_views = Self::create_image_views(&loader, &swapchain, &base.device, format.format);
+        let images = unsafe { loader.get_swapchain_images(swapchain).unwrap() };
+        let image_views = Self::create_image_views(&images, &base.device, format.format);
 
         Self {
             loader,
             swapchain,
+            images,
             image_views,
             capabilities,
             format,
@@ -58,7 +62,7 @@ impl Swapchain {
             image_color_space: self.format.color_space,
             image_extent,
             image_array_layers: 1,
-            image_usage: vk::ImageUsageFlags::COLOR_ATTACHMENT,
+            image_usage: Self::image_usage(&self.capabilities),
             image_sharing_mode: vk::SharingMode::EXCLUSIVE,
             queue_family_index_count: 1,
             p_queue_family_indices: &base.queue_family_index,
@@ -70,7 +74,18 @@ impl Swapchain {
         };
 
         self.swapchain = unsafe { self.loader.create_swapchain(&create_info, None).unwrap_unchecked() };
-        self.image_views = Self::create_image_views(&self.loader, &self.swapchain, &base.device, self.format.format);
+        self.images = unsafe { self.loader.get_swapchain_images(self.swapchain).unwrap() };
+        self.image_views = Self::create_image_views(&self.images, &base.device, self.format.format);
+    }
+
+    // Screenshots kopieren aus dem Swapchain-Bild, das geht nur, wenn die Oberfläche TRANSFER_SRC erlaubt
+    fn image_usage(capabilities: &SurfaceCapabilitiesKHR) -> vk::ImageUsageFlags {
+        vk::ImageUsageFlags::COLOR_ATTACHMENT | (capabilities.supported_usage_flags & vk::ImageUsageFlags::TRANSFER_SRC)
+    }
+
+    #[inline]
+    pub fn can_capture(&self) -> bool {
+        self.capabilities.supported_usage_flags.contains(vk::ImageUsageFlags::TRANSFER_SRC)
     }
 
     fn query_swap_chain_support(base: &VkBase) -> (SurfaceCapabilitiesKHR, vk::SurfaceFormatKHR, vk::PresentModeKHR){
@@ -106,7 +121,7 @@ impl Swapchain {
             image_color_space: format.color_space,
             image_extent,
             image_array_layers: 1,
-            image_usage: vk::ImageUsageFlags::COLOR_ATTACHMENT,
+            image_usage: Self::image_usage(capabilities),
             image_sharing_mode: vk::SharingMode::EXCLUSIVE,
             queue_family_index_count: 1,
             p_queue_family_indices: &queue_family_index,
@@ -121,11 +136,10 @@ impl Swapchain {
 
     }
 
-    fn create_image_views(swapchain_loader: &swapchain::Device, swapchain: &SwapchainKHR, device: &ash::Device, format: Format) -> Vec<vk::ImageView> {
-        let present_images = unsafe { swapchain_loader.get_swapchain_images(*swapchain).unwrap() };
+    fn create_image_views(present_images: &[vk::Image], device: &ash::Device, format: Format) -> Vec<vk::ImageView> {
         let mut present_image_views = Vec::with_capacity(present_images.len());
 
-        for present_image in present_images {
+        for &present_image in present_images {
             let create_info = vk::ImageViewCreateInfo {
                 image: present_image,
                 view_type: vk::ImageViewType::TYPE_2D,
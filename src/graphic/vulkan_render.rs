@@ -1,26 +1,29 @@
 #![allow(dead_code)]
 
-use std::{ptr, cell::RefCell, ffi::c_void, mem::size_of, ptr::null, rc::Rc, thread::sleep, time::{Duration, Instant}};
+use std::{ptr, cell::RefCell, ffi::c_void, mem::{self, size_of}, ptr::null, rc::Rc, thread::sleep, time::{Duration, Instant}};
 use ash::vk::{self, AccessFlags, AttachmentDescriptionFlags, BorderColor, CommandPoolCreateFlags, CompareOp, DescriptorImageInfo, DescriptorType, Extent3D, Filter, Format, Framebuffer, ImageLayout, ImageTiling, ImageUsageFlags, ImageView, MemoryPropertyFlags, PipelineStageFlags, SampleCountFlags, Sampler, SamplerAddressMode, SamplerCreateInfo, SamplerMipmapMode, ShaderStageFlags};
-use cgmath::{ortho, Point3, SquareMatrix, Vector3};
+use cgmath::{ortho, SquareMatrix};
 use cgmath::Matrix4;
 use iron_oxide::{graphics::{self, SinlgeTimeCommands, VkBase}, primitives::Vec2, ui::UiState};
 use winit::{dpi::PhysicalSize, raw_window_handle::HasDisplayHandle, window::Window};
 
-use crate::game::World;
+use crate::game::{playfield::Viewport, World};
 
-use super::{buffer::create_uniform_buffers, uniform_buffer_object::UniformBufferObject, InstanceData};
-use super::main_pipeline;
+use super::{buffer::create_uniform_buffers, instance_buffer::InstanceBuffer, uniform_buffer_object::UniformBufferObject, SpriteBatch};
+use super::{atlas, capture::{self, Capture}, main_pipeline, offscreen::{self, Offscreen}};
 
 pub const MAXFRAMESINFLIGHT: usize = 2;
 
 pub struct VulkanRender {
-    pub window: Window,
+    // Offscreen-Renderer haben kein Fenster
+    window: Option<Window>,
     pub window_size: PhysicalSize<u32>,
 
     pub base: iron_oxide::graphics::VkBase,
 
-    pub swapchain: super::Swapchain,
+    // Im Offscreen-Modus gibt es keine Swapchain, gezeichnet wird dann in `offscreen`
+    pub swapchain: Option<super::Swapchain>,
+    pub offscreen: Option<Offscreen>,
     pub render_pass: vk::RenderPass,
 
     pipeline_layout: vk::PipelineLayout,
@@ -32,7 +35,8 @@ pub struct VulkanRender {
     pub vertex_count: u32,
 
     pub instance_count: u32,
-    pub instance_buffer: graphics::Buffer,
+    pub instance_buffer: InstanceBuffer,
+    sprite_batch: SpriteBatch,
 
     uniform_buffers: [graphics::Buffer; MAXFRAMESINFLIGHT],
     uniform_buffers_mapped: [*mut c_void; MAXFRAMESINFLIGHT],
@@ -65,6 +69,10 @@ pub struct VulkanRender {
 
     pub depth_image: graphics::Image,
 
+    // Laufende Aufnahme, der Puffer passt zur Fenstergröße und lebt nur so lange wie die Aufnahme
+    capture: Option<Capture>,
+    capture_buffer: Option<(graphics::Buffer, vk::Extent2D)>,
+
     start_time: Duration,
 
     pub ui_state: Rc<RefCell<UiState>>,
@@ -74,33 +82,42 @@ pub struct VulkanRender {
 
 impl VulkanRender {
     pub fn create(window: Window, world: &World) -> Self {
-        let start_time = Instant::now();
-
         let base = VkBase::create(unsafe { ash_window::enumerate_required_extensions(window.display_handle().unwrap_unchecked().as_raw()).unwrap_unchecked()}.to_vec() as _, &window, 0);
+        let window_size = window.inner_size();
+        Self::create_with_target(base, Some(window), world, window_size)
+    }
+
+    // Zeichnet in ein eigenes Bild statt in die Swapchain, etwa für Screenshot-Tests ohne Bildschirm.
+    // Braucht weder Fenster noch Surface, auf CI reicht ein Softwaretreiber wie lavapipe.
+    pub fn create_offscreen(world: &World, size: PhysicalSize<u32>) -> Self {
+        Self::create_with_target(offscreen::create_headless_base(), None, world, size)
+    }
 
-        let rt_usage = vk::BufferUsageFlags::empty();
+    fn create_with_target(base: VkBase, window: Option<Window>, world: &World, window_size: PhysicalSize<u32>) -> Self {
+        let start_time = Instant::now();
 
         let command_pool = Self::create_command_pool(&base);
         let single_time_command_pool = Self::create_single_time_command_pool(&base);
 
-        let window_size = window.inner_size();
         let cmd_buf = SinlgeTimeCommands::begin(&base, &single_time_command_pool);
         let depth_image = Self::create_depth_resources(&base, &cmd_buf, Extent3D { width: window_size.width, height: window_size.height, depth: 1 });
         SinlgeTimeCommands::end(&base, &single_time_command_pool, cmd_buf);
 
-        let swapchain = super::Swapchain::create(&base, window_size);
+        let extent = vk::Extent2D { width: window_size.width, height: window_size.height };
+        let offscreen = window.is_none().then(|| Offscreen::create(&base, extent));
+        let swapchain = offscreen.is_none().then(|| super::Swapchain::create(&base, window_size));
 
-        let render_pass = Self::create_render_pass(&base, swapchain.format, true, true, false, true);
+        // Das Offscreen-Bild bleibt nach dem Pass ein Farbziel, read_pixels bringt es selbst ins Kopierlayout
+        let format = swapchain.as_ref().map_or(vk::SurfaceFormatKHR { format: Offscreen::FORMAT, color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR }, |swapchain| swapchain.format);
+        let render_pass = Self::create_render_pass(&base, format, true, true, false, swapchain.is_some());
 
         let descriptor_set_layout = create_descriptor_set_layout(&base.device);
         let ui_descriptor_set_layout = create_ui_descriptor_set_layout(&base.device);
-        let (pipeline_layout, pipeline) = main_pipeline::create_main_pipeline(&base.device, window_size, render_pass, &descriptor_set_layout);
+        let (pipeline_layout, pipeline) = main_pipeline::create_main_pipeline(&base.device, window_size, render_pass, &descriptor_set_layout, atlas::texture_size());
         let mut texture_image = Self::create_texture_image(&base, &single_time_command_pool);
         let mut font_atlas = Self::create_font_atlas(&base, &single_time_command_pool);
         let texture_sampler = Self::create_texture_sampler(&base.device);
 
-        let instances = world.get_instances();
-
         let vertex_count = 4;
 
         //let ui_instances;
@@ -110,9 +127,9 @@ impl VulkanRender {
         //    ui_instances = mut_ui.get_instaces(Vec2::new(window_size.width as f32, window_size.height as f32));
         //}
 
-        let ui_state = world.ui.clone();
+        let ui_state = world.hud.ui.clone();
 
-        let instance_buffer = graphics::Buffer::device_local(&base, &single_time_command_pool, size_of::<Matrix4<f32>>() as u64, instances.len() as u64, instances.as_ptr() as _, vk::BufferUsageFlags::VERTEX_BUFFER | rt_usage);
+        let instance_buffer = InstanceBuffer::create(&base);
 
         texture_image.create_view(&base, vk::ImageAspectFlags::COLOR);
         font_atlas.create_view(&base, vk::ImageAspectFlags::COLOR);
@@ -127,7 +144,12 @@ impl VulkanRender {
         //unsafe { device.destroy_descriptor_set_layout(descriptor_set_layout, None) };
         
         let command_buffers = Self::create_command_buffers(&base.device, &command_pool);
-        let main_framebuffer = Self::create_framebuffers(&base.device, &swapchain.image_views, &depth_image.view, &render_pass, window_size);
+        let image_views = match (&swapchain, &offscreen) {
+            (Some(swapchain), _) => swapchain.image_views.clone(),
+            (None, Some(offscreen)) => vec![offscreen.image.view],
+            (None, None) => unreachable!(),
+        };
+        let main_framebuffer = Self::create_framebuffers(&base.device, &image_views, &depth_image.view, &render_pass, window_size);
         let (image_available_semaphores, render_finsih_semaphores, in_flight_fences, world_fences, ui_fences)= Self::create_sync_object(&base.device);
 
         Self::init_ui_uniform_buffer(window_size, &ui_uniform_buffers_mapped);
@@ -141,6 +163,7 @@ impl VulkanRender {
             window_size,
             base,
             swapchain,
+            offscreen,
             pipeline_layout,
             render_pass,
             graphics_pipeline: pipeline,
@@ -150,8 +173,9 @@ impl VulkanRender {
     
             vertex_count,
 
-            instance_count: instances.len() as _,
+            instance_count: 0,
             instance_buffer,
+            sprite_batch: SpriteBatch::default(),
     
             uniform_buffers,
             uniform_buffers_mapped,
@@ -179,6 +203,9 @@ impl VulkanRender {
     
             texture_sampler,
             depth_image,
+
+            capture: None,
+            capture_buffer: None,
     
             start_time: Duration::new(0, 0),
             ui_state,
@@ -188,6 +215,10 @@ impl VulkanRender {
     }
 
     pub fn recreate_swapchain(&mut self, new_size: PhysicalSize<u32>) {
+        // Das Offscreen-Ziel behält seine Größe
+        let Some(swapchain) = &mut self.swapchain else {
+            return;
+        };
 
         self.window_size = new_size;
 
@@ -198,15 +229,15 @@ impl VulkanRender {
         }
 
         unsafe { self.base.device.device_wait_idle().unwrap_unchecked() };
-        unsafe { self.swapchain.destroy(&self.base.device, &self.main_framebuffer) };
+        unsafe { swapchain.destroy(&self.base.device, &self.main_framebuffer) };
         self.depth_image.destroy(&self.base.device);
 
         let cmd_buf = SinlgeTimeCommands::begin(&self.base, &self.single_time_command_pool);
         self.depth_image = Self::create_depth_resources(&self.base, &cmd_buf, Extent3D { width: self.window_size.width, height: self.window_size.height, depth: 1 });
         SinlgeTimeCommands::end(&self.base, &self.single_time_command_pool, cmd_buf);
 
-        self.swapchain.recreate(&self.base, new_size);
-        self.main_framebuffer = Self::create_framebuffers(&self.base.device, &self.swapchain.image_views, &self.depth_image.view, &self.render_pass, new_size);
+        swapchain.recreate(&self.base, new_size);
+        self.main_framebuffer = Self::create_framebuffers(&self.base.device, &swapchain.image_views, &self.depth_image.view, &self.render_pass, new_size);
         self.update_ui_uniform_buffer();
     }
 
@@ -385,6 +416,14 @@ impl VulkanRender {
             extent: vk::Extent2D { width: self.window_size.width, height: self.window_size.height },
         };
 
+        // Die Welt wird nur in den Bereich mit ihrem Seitenverhältnis gezeichnet, die UI nutzt das ganze Fenster
+        let playfield = Viewport::fit(self.window_size.width, self.window_size.height);
+        let world_view_port = vk::Viewport { x: playfield.x, y: playfield.y, width: playfield.width, height: playfield.height, ..view_port };
+        let world_scissor = vk::Rect2D {
+            offset: vk::Offset2D { x: playfield.x as i32, y: playfield.y as i32 },
+            extent: vk::Extent2D { width: playfield.width.round() as u32, height: playfield.height.round() as u32 },
+        };
+
         let device = &self.base.device;
         
         let begin_info = vk::CommandBufferBeginInfo {
@@ -401,15 +440,26 @@ impl VulkanRender {
             if self.renderer != 0 {
             } else {
                 device.cmd_begin_render_pass(self.command_buffers[self.current_frame], &render_pass_info, vk::SubpassContents::INLINE);
+                device.cmd_set_scissor(self.command_buffers[self.current_frame], 0, &[world_scissor]);
+                device.cmd_set_viewport(self.command_buffers[self.current_frame], 0, &[world_view_port]);
                 device.cmd_bind_pipeline(self.command_buffers[self.current_frame], vk::PipelineBindPoint::GRAPHICS, self.graphics_pipeline);
-                device.cmd_bind_vertex_buffers(self.command_buffers[self.current_frame], 0, &[self.instance_buffer.inner], &[0]);
+                device.cmd_bind_vertex_buffers(self.command_buffers[self.current_frame], 0, &[self.instance_buffer.buffer(self.current_frame)], &[0]);
                 device.cmd_bind_descriptor_sets(self.command_buffers[self.current_frame], vk::PipelineBindPoint::GRAPHICS, self.pipeline_layout, 0, &[self.descriptor_sets[self.current_frame]], &[]);
                 device.cmd_draw(self.command_buffers[self.current_frame], 4, self.instance_count, 0, 0);
             }
 
             device.cmd_next_subpass(self.command_buffers[self.current_frame], vk::SubpassContents::INLINE);
-            self.ui_state.borrow().draw(&self.base.device, self.command_buffers[self.current_frame], &self.ui_descriptor_sets[self.current_frame]);
+            device.cmd_set_scissor(self.command_buffers[self.current_frame], 0, &[scissor]);
+            device.cmd_set_viewport(self.command_buffers[self.current_frame], 0, &[view_port]);
+            if !self.capture.as_ref().is_some_and(|capture| capture.hide_ui) {
+                self.ui_state.borrow().draw(&self.base.device, self.command_buffers[self.current_frame], &self.ui_descriptor_sets[self.current_frame]);
+            }
             device.cmd_end_render_pass(self.command_buffers[self.current_frame]);
+
+            // Das Offscreen-Ziel wird erst nach dem Frame über read_pixels kopiert
+            if let (Some(swapchain), Some((buffer, extent)), true) = (&self.swapchain, &self.capture_buffer, self.capture.is_some()) {
+                capture::record_copy(device, self.command_buffers[self.current_frame], swapchain.images[index as usize], *extent, buffer.inner);
+            }
             
             device.end_command_buffer(self.command_buffers[self.current_frame]).unwrap();
         };
@@ -449,7 +499,16 @@ impl VulkanRender {
     pub fn draw_frame(&mut self) {
         let time = Instant::now();
 
-        let window_size = self.window.inner_size();
+        if self.swapchain.is_none() {
+            self.render_offscreen_frame();
+            if self.capture.is_some() {
+                let pixels = self.offscreen.as_ref().unwrap().read_pixels(&self.base, &self.single_time_command_pool);
+                self.finish_capture(pixels, Offscreen::FORMAT);
+            }
+            return;
+        }
+
+        let window_size = self.window().inner_size();
 
         if window_size.width == 0 || window_size.height == 0 {
             sleep(Duration::from_millis(25));
@@ -463,7 +522,8 @@ impl VulkanRender {
         };
 
         let image_index = unsafe { 
-            let result = self.swapchain.loader.acquire_next_image(self.swapchain.swapchain, u64::MAX, self.image_available_semaphores[self.current_frame], vk::Fence::null());
+            let swapchain = self.swapchain.as_ref().unwrap();
+            let result = swapchain.loader.acquire_next_image(swapchain.swapchain, u64::MAX, self.image_available_semaphores[self.current_frame], vk::Fence::null());
             match result {
                 Ok(result) => {
                     if result.1 {
@@ -475,17 +535,12 @@ impl VulkanRender {
             }
         };
 
-        if self.ui_state.borrow().dirty {
-            self.upload_ui();
+        let capturing = self.capture.is_some();
+        if capturing {
+            self.prepare_capture_buffer();
         }
-        
-        let instances = self.world().get_instances();
-        self.instance_count = instances.len() as u32;
-        self.instance_buffer.update(&self.base, &self.command_pool, size_of::<InstanceData>() as u64, instances.len() as u64, instances.as_ptr() as _, vk::BufferUsageFlags::VERTEX_BUFFER);
-
-        self.record_command_buffer(image_index);
-        self.update_uniform_buffer();
 
+        self.prepare_frame(image_index);
 
         let submit_info = vk::SubmitInfo {
             p_wait_semaphores: &self.image_available_semaphores[self.current_frame],
@@ -500,17 +555,31 @@ impl VulkanRender {
 
         unsafe { self.base.device.queue_submit(self.base.queue, &[submit_info], self.in_flight_fences[self.current_frame]).unwrap() };
 
+        // Aufnahme-Frames warten auf die GPU, damit der Puffer gelesen werden kann
+        if capturing {
+            unsafe { self.base.device.wait_for_fences(&[self.in_flight_fences[self.current_frame]], true, u64::MAX).unwrap() };
+            let (buffer, extent) = self.capture_buffer.as_ref().unwrap();
+            let size = extent.width as u64 * extent.height as u64 * 4;
+            let mut pixels = vec![0; size as usize];
+            let mapped = buffer.map_memory(&self.base.device, size);
+            unsafe { ptr::copy_nonoverlapping(mapped as *const u8, pixels.as_mut_ptr(), pixels.len()) };
+            buffer.unmap_memory(&self.base.device);
+            let format = self.swapchain.as_ref().unwrap().format.format;
+            self.finish_capture(pixels, format);
+        }
+
+        let swapchain = self.swapchain.as_ref().unwrap();
         let present_info = vk::PresentInfoKHR {
             wait_semaphore_count: 1,
             p_wait_semaphores: &self.render_finsih_semaphores[self.current_frame],
             swapchain_count: 1,
-            p_swapchains: &self.swapchain.swapchain,
+            p_swapchains: &swapchain.swapchain,
             p_image_indices: &image_index,
             ..Default::default()
         };
 
-        if unsafe { self.swapchain.loader.queue_present(self.base.queue, &present_info).is_err() } {
-            self.recreate_swapchain(self.window.inner_size());
+        if unsafe { swapchain.loader.queue_present(self.base.queue, &present_info).is_err() } {
+            self.recreate_swapchain(self.window().inner_size());
             return;
         }
 
@@ -518,18 +587,120 @@ impl VulkanRender {
         self.start_time += time.elapsed();
     }
 
-    #[inline]
-    fn update_uniform_buffer(&mut self) {
+    // Gemeinsamer Teil von Swapchain- und Offscreen-Frames: UI, Instanzen, Kommandos und Uniforms
+    fn prepare_frame(&mut self, image_index: u32) {
+        if self.ui_state.borrow().dirty {
+            self.upload_ui();
+        }
 
-        let aspect = 1.0 - (self.window_size.width as f32 - 400.0) / self.window_size.width as f32;
+        let mut batch = mem::take(&mut self.sprite_batch);
+        batch.clear();
+        self.world().draw(&mut batch);
+        // Die Fence dieses Frames ist schon abgewartet, sein Instanzpuffer wird also nicht mehr gelesen
+        self.instance_count = self.instance_buffer.write(&self.base, self.current_frame, &batch);
+        self.sprite_batch = batch;
 
-        let bottom_view = self.world().current_view;
+        self.record_command_buffer(image_index);
+        self.update_uniform_buffer();
+    }
 
-        let ubo = UniformBufferObject {
-            view: Matrix4::look_at_rh(Point3::new(0.0, -(self.window_size.height as f32) * aspect - bottom_view, 1.0), Point3::new(0.0, -(self.window_size.height as f32) * aspect - bottom_view, 0.0), Vector3::unit_y()),
-            proj: ortho(0.0, self.window_size.width.min(400) as f32, 0.0, self.window_size.height as f32 * aspect, -100.0, 100.0),
+    // Zeichnet einen Frame ins Offscreen-Ziel und wartet, bis er fertig ist
+    fn render_offscreen_frame(&mut self) {
+        let fence = self.in_flight_fences[self.current_frame];
+        unsafe {
+            self.base.device.wait_for_fences(&[fence], true, u64::MAX).unwrap();
+            self.base.device.reset_fences(&[fence]).unwrap();
+            self.base.device.reset_command_buffer(self.command_buffers[self.current_frame], vk::CommandBufferResetFlags::empty()).unwrap()
+        };
+
+        self.prepare_frame(0);
+
+        let submit_info = vk::SubmitInfo {
+            command_buffer_count: 1,
+            p_command_buffers: &self.command_buffers[self.current_frame],
+            ..Default::default()
+        };
+
+        unsafe {
+            self.base.device.queue_submit(self.base.queue, &[submit_info], fence).unwrap();
+            self.base.device.wait_for_fences(&[fence], true, u64::MAX).unwrap();
+        };
+    }
+
+    // Zeichnet Welt und UI und liefert das Bild als RGBA in sRGB, Zeile für Zeile von oben
+    pub fn render_offscreen(&mut self) -> Vec<u8> {
+        assert!(self.offscreen.is_some(), "renderer was created without an offscreen target");
+        self.render_offscreen_frame();
+        self.offscreen.as_ref().unwrap().read_pixels(&self.base, &self.single_time_command_pool)
+    }
+
+    // Startet einen Screenshot oder eine Serie, die folgenden Frames werden als PNG gespeichert
+    pub fn request_capture(&mut self, capture: Capture) {
+        if self.capture.is_some() {
+            log::warn!("a capture is already running");
+            return;
+        }
+        if self.swapchain.as_ref().is_some_and(|swapchain| !swapchain.can_capture()) {
+            log::error!("the surface does not allow copying from swapchain images");
+            return;
+        }
+        self.capture = Some(capture);
+    }
+
+    // Legt den Host-Puffer für die aktuelle Fenstergröße an. Der alte wird von keinem laufenden Frame mehr benutzt,
+    // weil jeder Aufnahme-Frame auf die GPU wartet.
+    fn prepare_capture_buffer(&mut self) {
+        let extent = vk::Extent2D { width: self.window_size.width, height: self.window_size.height };
+        if self.capture_buffer.as_ref().is_some_and(|(_, current)| *current == extent) {
+            return;
+        }
+        if let Some((buffer, _)) = self.capture_buffer.take() {
+            buffer.destroy(&self.base.device);
+        }
+        let size = extent.width as u64 * extent.height as u64 * 4;
+        let buffer = graphics::Buffer::create(&self.base, size, vk::BufferUsageFlags::TRANSFER_DST, vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT);
+        self.capture_buffer = Some((buffer, extent));
+    }
+
+    fn finish_capture(&mut self, pixels: Vec<u8>, format: vk::Format) {
+        let Some(capture) = &mut self.capture else {
+            return;
+        };
+
+        let path = capture.next_path();
+        let done = match capture::to_rgba(format, pixels) {
+            Some(rgba) => {
+                capture::save_png(path, self.window_size.width, self.window_size.height, rgba);
+                capture.remaining == 0
+            },
+            None => {
+                log::error!("can not capture images in format {format:?}");
+                true
+            },
         };
 
+        if done {
+            self.capture = None;
+            if let Some((buffer, _)) = self.capture_buffer.take() {
+                buffer.destroy(&self.base.device);
+            }
+        }
+    }
+
+    // Hängt einen UI-Baum an den Renderer und baut ihn für die aktuelle Größe auf
+    pub fn attach_ui(&mut self, ui: Rc<RefCell<UiState>>) {
+        ui.borrow_mut().init_graphics(&self.base, &self.window_size, self.render_pass, &self.ui_descriptor_set_layout);
+        self.ui_state = ui;
+        let window_size = self.window_size;
+        self.update_ui(window_size);
+    }
+
+    #[inline]
+    fn update_uniform_buffer(&mut self) {
+
+        let (view, proj) = self.world().camera_matrices();
+        let ubo = UniformBufferObject { view, proj };
+
         for uniform_buffer in self.uniform_buffers_mapped {
 
             unsafe { ptr::copy_nonoverlapping(&ubo as *const UniformBufferObject, uniform_buffer as _, 1) };
@@ -559,7 +730,7 @@ impl VulkanRender {
     }
 
     fn create_texture_image(base: &VkBase, command_pool: &vk::CommandPool) -> graphics::Image {
-        let decoder = png::Decoder::new(&include_bytes!("../../textures/texture.png")[..]);
+        let decoder = png::Decoder::new(atlas::TEXTURE);
 
         let mut reader = decoder.read_info().unwrap();
         let mut buf = vec![0; reader.output_buffer_size()];
@@ -662,6 +833,10 @@ impl VulkanRender {
         self.ui_state.borrow_mut().upload(&self.base, Vec2::new(self.window_size.width as f32, self.window_size.height as f32), &self.single_time_command_pool);
     }
 
+    pub fn window(&self) -> &Window {
+        self.window.as_ref().expect("offscreen renderer has no window")
+    }
+
     const fn world(&self) -> &World {
         unsafe { &*self.world }
     }
@@ -670,7 +845,9 @@ impl VulkanRender {
     pub fn destroy(&mut self) {
         let device = &self.base.device;
         #[cfg(debug_assertions)]
-        unsafe { self.base.debug_utils.destroy_debug_utils_messenger(self.base.utils_messenger, None) };
+        if self.base.utils_messenger != vk::DebugUtilsMessengerEXT::null() {
+            unsafe { self.base.debug_utils.destroy_debug_utils_messenger(self.base.utils_messenger, None) };
+        }
         for i in 0..MAXFRAMESINFLIGHT {
             unsafe {
                 device.destroy_semaphore(self.image_available_semaphores[i], None);
@@ -691,12 +868,25 @@ impl VulkanRender {
             device.destroy_descriptor_pool(self.descriptor_pool, None);
             device.destroy_descriptor_pool(self.ui_descriptor_pool, None);
             device.destroy_render_pass(self.render_pass, None);
-            self.swapchain.destroy(device, &self.main_framebuffer);
+            match &mut self.swapchain {
+                Some(swapchain) => swapchain.destroy(device, &self.main_framebuffer),
+                None => self.main_framebuffer.iter().for_each(|framebuffer| device.destroy_framebuffer(*framebuffer, None)),
+            }
+            if let Some(offscreen) = &self.offscreen {
+                offscreen.destroy(device);
+            }
+            self.instance_buffer.destroy(device);
+            if let Some((buffer, _)) = &self.capture_buffer {
+                buffer.destroy(device);
+            }
             device.destroy_sampler(self.texture_sampler, None);
             self.depth_image.destroy(device);
             self.texture_image.destroy(device);
             self.font_atlas.destroy(device);
-            self.base.surface_loader.destroy_surface(self.base.surface, None);
+            // Die Headless-Basis hat keine Surface und lädt die Erweiterung gar nicht erst
+            if self.base.surface != vk::SurfaceKHR::null() {
+                self.base.surface_loader.destroy_surface(self.base.surface, None);
+            }
             device.destroy_device(None);
             self.base.instance.destroy_instance(None);
         };
@@ -6,11 +6,10 @@ mod game;
 #[cfg(target_os = "android")]
 mod android {
     use activity::AndroidApp;
-    use iron_oxide::ui::{ErasedFnPointer, UiType};
     use winit::platform::android::EventLoopBuilderExtAndroid;
     use winit::platform::android::*;
     use log::info;
-    use crate::game::{app::App, World};
+    use crate::game::app::App;
     use winit::event_loop::{EventLoop, EventLoopBuilder};
 
     #[unsafe(no_mangle)]
@@ -19,18 +18,20 @@ mod android {
         android_logger::init_once(android_logger::Config::default().with_max_level(log::LevelFilter::max()));
         log::info!("Running mainloop...");
 
+        if let Some(path) = app.internal_data_path() {
+            crate::game::storage::set_data_dir(path);
+        }
+
         let event_loop: EventLoop<()> = EventLoopBuilder::default().with_android_app(app).build().unwrap();
 
         let mut application = App::run();
+        // application bleibt bis zum Ende der Eventschleife an dieser Stelle
+        unsafe { application.connect_ui() };
 
-        {
-            let mut ui = application.ui.borrow_mut();
-            let element = unsafe { ui.get_element_mut(vec![2]).unwrap() };
-    
-            if let UiType::Button(button) = &mut element.inherit {
-                button.on_press = ErasedFnPointer::from_associated_ui(&mut application.world, World::restart);
-            }
-        };
+        // Die App besitzt den Sensor und schaltet ihn beim Pausieren ab
+        if let Some(accelerometer) = sensor::Accelerometer::create() {
+            application.set_tilt_source(Box::new(accelerometer));
+        }
         
         info!("between");
         event_loop.run_app(&mut application).unwrap();
@@ -44,4 +45,104 @@ mod android {
             log::error!("Panic occurred: {:?}", info);
         }));
     }
-}
\ No newline at end of file
+
+    mod sensor {
+        use std::{cell::Cell, ffi::{c_int, c_void}, ptr};
+
+        use ndk_sys::*;
+
+        use crate::game::controls::TiltSensor;
+
+        const LOOPER_ID: c_int = 3;
+        // 60 Hz reichen für die Lenkung
+        const EVENT_INTERVAL_US: i32 = 16_667;
+
+        struct Shared {
+            queue: *mut ASensorEventQueue,
+            tilt: Cell<f32>,
+        }
+
+        // Der Sensor meldet sich über den Looper des Hauptthreads, den auch winit abfragt.
+        pub struct Accelerometer {
+            manager: *mut ASensorManager,
+            sensor: *const ASensor,
+            shared: Box<Shared>,
+            enabled: bool,
+        }
+
+        impl Accelerometer {
+            pub fn create() -> Option<Self> {
+                unsafe {
+                    let manager = ASensorManager_getInstanceForPackage(c"vudeljump".as_ptr());
+                    let looper = ALooper_forThread();
+                    if manager.is_null() || looper.is_null() {
+                        return None;
+                    }
+                    let sensor = ASensorManager_getDefaultSensor(manager, ASENSOR_TYPE_ACCELEROMETER as c_int);
+                    if sensor.is_null() {
+                        log::info!("no accelerometer, tilt steering disabled");
+                        return None;
+                    }
+
+                    let mut shared = Box::new(Shared { queue: ptr::null_mut(), tilt: Cell::new(0.0) });
+                    let data = &mut *shared as *mut Shared as *mut c_void;
+                    shared.queue = ASensorManager_createEventQueue(manager, looper, LOOPER_ID, Some(on_sensor_events), data);
+                    if shared.queue.is_null() {
+                        log::error!("could not create sensor event queue");
+                        return None;
+                    }
+
+                    let mut accelerometer = Self { manager, sensor, shared, enabled: false };
+                    accelerometer.set_enabled(true);
+                    Some(accelerometer)
+                }
+            }
+        }
+
+        impl TiltSensor for Accelerometer {
+            fn acceleration_x(&self) -> f32 {
+                self.shared.tilt.get()
+            }
+
+            fn set_enabled(&mut self, enabled: bool) {
+                if enabled == self.enabled {
+                    return;
+                }
+                self.enabled = enabled;
+                unsafe {
+                    if enabled {
+                        ASensorEventQueue_enableSensor(self.shared.queue, self.sensor);
+                        ASensorEventQueue_setEventRate(self.shared.queue, self.sensor, EVENT_INTERVAL_US);
+                    } else {
+                        ASensorEventQueue_disableSensor(self.shared.queue, self.sensor);
+                        self.shared.tilt.set(0.0);
+                    }
+                }
+            }
+        }
+
+        impl Drop for Accelerometer {
+            fn drop(&mut self) {
+                self.set_enabled(false);
+                unsafe { ASensorManager_destroyEventQueue(self.manager, self.shared.queue) };
+            }
+        }
+
+        unsafe extern "C" fn on_sensor_events(_fd: c_int, _events: c_int, data: *mut c_void) -> c_int {
+            let shared = unsafe { &*(data as *const Shared) };
+            let mut events: [ASensorEvent; 8] = unsafe { std::mem::zeroed() };
+            loop {
+                let count = unsafe { ASensorEventQueue_getEvents(shared.queue, events.as_mut_ptr(), events.len()) };
+                if count <= 0 {
+                    break;
+                }
+                // Beim Beschleunigungssensor enthält die Union der Messwerte den Vektor, x zuerst
+                let last = &events[count as usize - 1];
+                let x = unsafe { last.__bindgen_anon_1.__bindgen_anon_1.acceleration.__bindgen_anon_1.v[0] };
+                shared.tilt.set(x);
+            }
+            // 1 hält den Callback am Looper registriert
+            1
+        }
+    }
+}
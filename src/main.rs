@@ -1,7 +1,6 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use game::{app::App, World};
-use iron_oxide::ui::{UiType, ErasedFnPointer};
+use game::app::App;
 use winit::event_loop::EventLoop;
 
 mod graphic;
@@ -11,15 +10,8 @@ fn main() {
     let event_loop = EventLoop::new().unwrap();
     event_loop.set_control_flow(winit::event_loop::ControlFlow::Poll);
     let mut application = App::run();
-
-    {
-        let mut ui = application.ui.borrow_mut();
-        let element = unsafe { ui.get_element_mut(vec![2]).unwrap() };
-
-        if let UiType::Button(button) = &mut element.inherit {
-            button.on_press = ErasedFnPointer::from_associated_ui(&mut application.world, World::restart);
-        }
-    };
+    // application bleibt bis zum Ende der Eventschleife an dieser Stelle
+    unsafe { application.connect_ui() };
 
     event_loop.run_app(&mut application).unwrap();
     drop(application.renderer)